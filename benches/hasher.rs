@@ -0,0 +1,51 @@
+//! Compares chunk dispatch lookups using the default and `FourCC` hashers.
+//!
+//! Run with `cargo bench --bench hasher`.
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use fourcc::hash::BuildFourCCHasher;
+use fourcc::FourCC;
+
+const CODES: [&str; 16] = [
+    "RIFF", "LIST", "fmt ", "data", "fact", "cue ", "INFO", "INAM",
+    "avih", "strh", "strf", "movi", "idx1", "JUNK", "VP8 ", "VP8X",
+];
+const LOOKUPS: usize = 10_000_000;
+
+fn bench<S: BuildHasher + Default>(name: &str, stream: &[FourCC]) -> Duration {
+    let table: HashMap<FourCC, usize, S> = CODES.iter()
+        .enumerate()
+        .map(|(i, &code)| (FourCC::from_str_const(code), i))
+        .collect();
+    let start = Instant::now();
+    let mut sum = 0;
+    for code in stream {
+        sum += table.get(black_box(code)).copied().unwrap_or(0);
+    }
+    let elapsed = start.elapsed();
+    black_box(sum);
+    println!("{name:>12}: {:>6.2} ns/lookup", elapsed.as_nanos() as f64 / stream.len() as f64);
+    elapsed
+}
+
+fn main() {
+    // deterministic mix of known and unknown chunk identifiers
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let stream: Vec<FourCC> = (0..LOOKUPS).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        match state % 20 {
+            n @ 0..=15 => FourCC::from_str_const(CODES[n as usize]),
+            _ => FourCC::from_u32_be(state as u32),
+        }
+    }).collect();
+
+    let default = bench::<std::collections::hash_map::RandomState>("SipHash", &stream);
+    let fourcc = bench::<BuildFourCCHasher>("FourCCHasher", &stream);
+    println!("{:>12}: {:>6.2}x", "speedup", default.as_secs_f64() / fourcc.as_secs_f64());
+}
@@ -0,0 +1,41 @@
+//! Compares the throughput of the scalar and SIMD scanning methods.
+//!
+//! Run with `cargo bench --bench scan`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use fourcc::scan::{self, Method};
+use fourcc::FourCC;
+
+const CODES: [&str; 4] = ["RIFF", "FORM", "moov", "mdat"];
+const SIZE: usize = 64 << 20;
+
+fn bench(method: Method, haystack: &[u8], codes: &[FourCC]) -> Duration {
+    let start = Instant::now();
+    let found = scan::find_all(black_box(haystack), codes).method(method).count();
+    let elapsed = start.elapsed();
+    black_box(found);
+    println!("{:>12}: {:>8.2} MiB/s ({found} found)", format!("{method:?}"), SIZE as f64 / (1 << 20) as f64 / elapsed.as_secs_f64());
+    elapsed
+}
+
+fn main() {
+    // deterministic noise with occasional codes
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut haystack: Vec<u8> = (0..SIZE).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as u8
+    }).collect();
+    for (i, offset) in (0..SIZE - 4).step_by(4099).enumerate() {
+        haystack[offset..offset + 4].copy_from_slice(CODES[i % CODES.len()].as_bytes());
+    }
+    let codes: Vec<FourCC> = CODES.iter().map(|&code| FourCC::from_str_const(code)).collect();
+
+    let scalar = bench(Method::Scalar, &haystack, &codes);
+    let detected = Method::detect();
+    let simd = bench(detected, &haystack, &codes);
+    println!("{:>12}: {:>8.2}x", "speedup", scalar.as_secs_f64() / simd.as_secs_f64());
+}
@@ -0,0 +1,319 @@
+//! Derive macro mapping enums to and from `FourCC`.
+//!
+//! See [`FourCCEnum`] for details, the macro is re-exported by the `fourcc`
+//! crate when its `derive` feature is enabled.
+
+use proc_macro::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+
+/// Derives conversions between an enum and `FourCC`.
+///
+/// Each unit variant carries a `#[fourcc = "code"]` attribute. A single tuple
+/// variant holding a `FourCC` may be marked `#[fourcc(other)]` to catch codes
+/// without a variant. Characters up to `U+00FF` map to a single byte, so
+/// `"\u{a9}nam"` is the QuickTime `©nam` code.
+///
+/// The derive generates:
+/// * `const fn fourcc(&self) -> FourCC`
+/// * `const ALL: &[Self]` listing the unit variants in declaration order
+/// * `From<Self> for FourCC`
+/// * `TryFrom<FourCC>`, failing with the unknown code unless a catch-all
+///   variant exists
+/// * `Display`, formatting the code
+///
+/// # Examples
+/// ```
+/// use fourcc::FourCC;
+/// use fourcc_derive::FourCCEnum;
+///
+/// #[derive(FourCCEnum, Debug, PartialEq)]
+/// enum Codec {
+///     #[fourcc = "avc1"]
+///     Avc,
+///     #[fourcc = "hvc1"]
+///     Hevc,
+///     #[fourcc(other)]
+///     Other(FourCC),
+/// }
+///
+/// assert_eq!(Codec::try_from(FourCC(*b"avc1")), Ok(Codec::Avc));
+/// assert_eq!(Codec::try_from(FourCC(*b"av01")), Ok(Codec::Other(FourCC(*b"av01"))));
+/// assert_eq!(FourCC::from(Codec::Hevc), "hvc1");
+/// assert_eq!(Codec::ALL, [Codec::Avc, Codec::Hevc]);
+/// ```
+#[proc_macro_derive(FourCCEnum, attributes(fourcc))]
+pub fn derive_fourcc_enum(input: TokenStream) -> TokenStream {
+    match parse_enum(input) {
+        Ok(item) => generate(&item).parse().expect("generated code is valid"),
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Compilation error reported at a span.
+struct Error {
+    span: Span,
+    msg: String,
+}
+
+impl Error {
+    fn new(span: Span, msg: impl Into<String>) -> Self
+        { Self { span, msg: msg.into() } }
+
+    // Expands to `compile_error!("msg")` located at the error span.
+    fn into_compile_error(self) -> TokenStream {
+        let mut msg = Literal::string(&self.msg);
+        msg.set_span(self.span);
+        let mut group = Group::new(Delimiter::Parenthesis, TokenTree::Literal(msg).into());
+        group.set_span(self.span);
+        let tokens: TokenStream = "::core::compile_error!".parse().unwrap();
+        tokens.into_iter()
+            .chain([TokenTree::Group(group), punct(';')])
+            .map(|mut token| { token.set_span(self.span); token })
+            .collect()
+    }
+}
+
+fn punct(c: char) -> TokenTree
+    { TokenTree::Punct(proc_macro::Punct::new(c, proc_macro::Spacing::Alone)) }
+
+/// Mapping of an enum variant.
+enum Mapping {
+    /// Unit variant with a code.
+    Code([u8; 4]),
+    /// Tuple variant holding unknown codes.
+    Other,
+}
+
+struct Variant {
+    name: String,
+    mapping: Mapping,
+}
+
+struct Enum {
+    name: String,
+    variants: Vec<Variant>,
+}
+
+//------------------------------------------------------------------------------
+
+fn parse_enum(input: TokenStream) -> Result<Enum, Error> {
+    let mut tokens = input.into_iter();
+    let mut name = None;
+    let mut body = None;
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident.to_string() == "struct" || ident.to_string() == "union" => {
+                return Err(Error::new(ident.span(), "FourCCEnum can only be derived for enums"))
+            },
+            TokenTree::Ident(ident) if ident.to_string() == "enum" => {
+                match tokens.next() {
+                    Some(TokenTree::Ident(ident)) => name = Some(ident.to_string()),
+                    _ => return Err(Error::new(ident.span(), "expected enum name")),
+                }
+                match tokens.next() {
+                    Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => body = Some(group),
+                    Some(token) => return Err(Error::new(token.span(), "FourCCEnum does not support generic enums")),
+                    None => return Err(Error::new(ident.span(), "expected enum body")),
+                }
+                break
+            },
+            _ => {},
+        }
+    }
+    let (name, body) = match (name, body) {
+        (Some(name), Some(body)) => (name, body),
+        _ => return Err(Error::new(Span::call_site(), "FourCCEnum can only be derived for enums")),
+    };
+
+    let mut variants = Vec::new();
+    let mut tokens: Vec<TokenTree> = Vec::new();
+    for token in body.stream().into_iter().chain([punct(',')]) {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == ',' => {
+                if !tokens.is_empty() { variants.push(parse_variant(&tokens, body.span())?) }
+                tokens.clear();
+            },
+            token => tokens.push(token),
+        }
+    }
+
+    let mut seen: Vec<([u8; 4], &str)> = Vec::new();
+    let mut other = None;
+    for variant in &variants {
+        match variant.mapping {
+            Mapping::Code(code) => {
+                if let Some((_, first)) = seen.iter().find(|(c, _)| *c == code) {
+                    return Err(Error::new(body.span(), format!("variants `{first}` and `{}` have the same code", variant.name)))
+                }
+                seen.push((code, &variant.name));
+            },
+            Mapping::Other => {
+                if let Some(first) = other.replace(&variant.name) {
+                    return Err(Error::new(body.span(), format!("variants `{first}` and `{}` are both marked `#[fourcc(other)]`", variant.name)))
+                }
+            },
+        }
+    }
+    Ok(Enum { name, variants })
+}
+
+fn parse_variant(tokens: &[TokenTree], span: Span) -> Result<Variant, Error> {
+    let mut mapping = None;
+    let mut name = None;
+    let mut fields = None;
+    let mut iter = tokens.iter();
+    while let Some(token) = iter.next() {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == '#' => {
+                let Some(TokenTree::Group(attr)) = iter.next() else {
+                    return Err(Error::new(p.span(), "expected attribute"))
+                };
+                if let Some(parsed) = parse_attribute(attr)? {
+                    if mapping.replace(parsed).is_some() {
+                        return Err(Error::new(attr.span(), "duplicate `fourcc` attribute"))
+                    }
+                }
+            },
+            TokenTree::Ident(ident) if name.is_none() => name = Some(ident.clone()),
+            TokenTree::Group(group) if name.is_some() => fields = Some(group.clone()),
+            TokenTree::Punct(p) if p.as_char() == '=' => {
+                return Err(Error::new(p.span(), "FourCCEnum variants cannot have discriminants"))
+            },
+            _ => {},
+        }
+    }
+    let Some(ident) = name else { return Err(Error::new(span, "expected variant")) };
+    let variant = ident.to_string();
+    match (mapping, fields) {
+        (Some(Mapping::Code(code)), None) => Ok(Variant { name: variant, mapping: Mapping::Code(code) }),
+        (Some(Mapping::Code(_)), Some(fields)) => {
+            Err(Error::new(fields.span(), format!("variant `{variant}` with a code must be a unit variant")))
+        },
+        (Some(Mapping::Other), Some(fields)) if fields.delimiter() == Delimiter::Parenthesis
+            && !fields.stream().into_iter().any(|t| matches!(t, TokenTree::Punct(ref p) if p.as_char() == ',')) => {
+            Ok(Variant { name: variant, mapping: Mapping::Other })
+        },
+        (Some(Mapping::Other), _) => {
+            Err(Error::new(ident.span(), format!("variant `{variant}` marked `#[fourcc(other)]` must hold a single `FourCC`")))
+        },
+        (None, _) => {
+            Err(Error::new(ident.span(), format!("variant `{variant}` is missing a `#[fourcc = \"...\"]` attribute")))
+        },
+    }
+}
+
+// Parses the contents of `#[...]`, returning `None` for unrelated attributes.
+fn parse_attribute(attr: &Group) -> Result<Option<Mapping>, Error> {
+    let tokens: Vec<TokenTree> = attr.stream().into_iter().collect();
+    match tokens.as_slice() {
+        [TokenTree::Ident(ident), rest @ ..] if ident.to_string() == "fourcc" => match rest {
+            [TokenTree::Punct(eq), TokenTree::Literal(lit)] if eq.as_char() == '=' => {
+                parse_code(lit).map(|code| Some(Mapping::Code(code)))
+            },
+            [TokenTree::Group(args)] if args.stream().to_string() == "other" => Ok(Some(Mapping::Other)),
+            _ => Err(Error::new(attr.span(), "expected `#[fourcc = \"code\"]` or `#[fourcc(other)]`")),
+        },
+        _ => Ok(None),
+    }
+}
+
+// Decodes a string literal into the four bytes of a code.
+fn parse_code(lit: &Literal) -> Result<[u8; 4], Error> {
+    let error = || Error::new(lit.span(), "expected a string literal of four characters up to U+00FF");
+    let text = lit.to_string();
+    let value = if let Some(raw) = text.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        raw.get(hashes + 1..raw.len() - hashes - 1).ok_or_else(error)?.to_string()
+    } else {
+        unescape(text.strip_prefix('"').and_then(|s| s.strip_suffix('"')).ok_or_else(error)?).ok_or_else(error)?
+    };
+    let bytes: Vec<u8> = value.chars()
+        .map(|c| u8::try_from(c as u32).map_err(|_| error()))
+        .collect::<Result<_, _>>()?;
+    bytes.try_into().map_err(|_| error())
+}
+
+// Resolves the escape sequences of a string literal body.
+fn unescape(s: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' { out.push(c); continue }
+        let c = match chars.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            c @ ('\\' | '\'' | '"') => c,
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                char::from(u8::from_str_radix(&hex, 16).ok()?)
+            },
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let end = rest.find('}')?;
+                let c = char::from_u32(u32::from_str_radix(&rest[..end], 16).ok()?)?;
+                chars = rest[end + 1..].chars();
+                c
+            },
+            _ => return None,
+        };
+        out.push(c);
+    }
+    Some(out)
+}
+
+//------------------------------------------------------------------------------
+
+fn generate(item: &Enum) -> String {
+    let name = &item.name;
+    let fourcc = |code: &[u8; 4]| format!("::fourcc::FourCC([{}u8, {}u8, {}u8, {}u8])", code[0], code[1], code[2], code[3]);
+    let mut to_code = String::new();
+    let mut from_code = String::new();
+    let mut all = String::new();
+    let mut fallback = String::from("_ => ::core::result::Result::Err(code),");
+    for variant in &item.variants {
+        let variant_name = &variant.name;
+        match &variant.mapping {
+            Mapping::Code(code) => {
+                to_code += &format!("{name}::{variant_name} => {},", fourcc(code));
+                from_code += &format!("{} => ::core::result::Result::Ok({name}::{variant_name}),", fourcc(code));
+                all += &format!("{name}::{variant_name},");
+            },
+            Mapping::Other => {
+                to_code += &format!("{name}::{variant_name}(code) => *code,");
+                fallback = format!("_ => ::core::result::Result::Ok({name}::{variant_name}(code)),");
+            },
+        }
+    }
+    format!("
+        impl {name} {{
+            /// Every variant with a four character code, in declaration order.
+            pub const ALL: &'static [Self] = &[{all}];
+
+            /// Returns the four character code of the variant.
+            pub const fn fourcc(&self) -> ::fourcc::FourCC {{
+                match self {{ {to_code} }}
+            }}
+        }}
+
+        impl ::core::convert::From<{name}> for ::fourcc::FourCC {{
+            fn from(value: {name}) -> Self {{ value.fourcc() }}
+        }}
+
+        impl ::core::convert::TryFrom<::fourcc::FourCC> for {name} {{
+            type Error = ::fourcc::FourCC;
+
+            fn try_from(code: ::fourcc::FourCC) -> ::core::result::Result<Self, Self::Error> {{
+                match code {{ {from_code} {fallback} }}
+            }}
+        }}
+
+        impl ::core::fmt::Display for {name} {{
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {{
+                ::core::fmt::Display::fmt(&self.fourcc(), f)
+            }}
+        }}
+    ")
+}
@@ -0,0 +1,56 @@
+use fourcc::FourCC;
+use fourcc_derive::FourCCEnum;
+
+#[derive(FourCCEnum, Debug, Clone, Copy, PartialEq)]
+enum Chunk {
+    #[fourcc = "fmt "]
+    Format,
+    #[fourcc = "data"]
+    Data,
+    /// Metadata title
+    #[fourcc = "\u{a9}nam"]
+    Name,
+    #[fourcc(other)]
+    Unknown(FourCC),
+}
+
+#[derive(FourCCEnum, Debug, PartialEq)]
+pub enum Closed {
+    #[fourcc = r"a\b "]
+    Raw,
+    #[fourcc = "\x00\x01\x02\x03"]
+    Escaped,
+}
+
+#[test]
+fn into_fourcc() {
+    assert_eq!(FourCC::from(Chunk::Format), "fmt ");
+    assert_eq!(Chunk::Data.fourcc(), "data");
+    assert_eq!(Chunk::Name.fourcc(), FourCC([0xa9, b'n', b'a', b'm']));
+    assert_eq!(FourCC::from(Chunk::Unknown(FourCC(*b"JUNK"))), "JUNK");
+    assert_eq!(FourCC::from(Closed::Raw), FourCC(*b"a\\b "));
+    assert_eq!(FourCC::from(Closed::Escaped), FourCC([0, 1, 2, 3]));
+}
+
+#[test]
+fn try_from_fourcc() {
+    assert_eq!(Chunk::try_from(FourCC(*b"data")), Ok(Chunk::Data));
+    assert_eq!(Chunk::try_from(FourCC([0xa9, b'n', b'a', b'm'])), Ok(Chunk::Name));
+    assert_eq!(Chunk::try_from(FourCC(*b"JUNK")), Ok(Chunk::Unknown(FourCC(*b"JUNK"))));
+    assert_eq!(Closed::try_from(FourCC(*b"JUNK")), Err(FourCC(*b"JUNK")));
+}
+
+#[test]
+fn round_trip() {
+    for &chunk in Chunk::ALL {
+        assert_eq!(Chunk::try_from(FourCC::from(chunk)), Ok(chunk));
+    }
+    assert_eq!(Chunk::ALL, [Chunk::Format, Chunk::Data, Chunk::Name]);
+    assert_eq!(Closed::ALL.len(), 2);
+}
+
+#[test]
+fn display() {
+    assert_eq!(Chunk::Format.to_string(), "fmt ");
+    assert_eq!(Chunk::Unknown(FourCC(*b"JUNK")).to_string(), "JUNK");
+}
@@ -0,0 +1,338 @@
+//! Sorted collections keyed by four character codes.
+//!
+//! [`FourCCMap`] and [`FourCCSet`] are ordered by a [`KeyOrder`] adapter,
+//! byte-lexicographic by default, which allows range queries by prefix.
+//!
+//! # Examples
+//! ```
+//! use fourcc::FourCC;
+//! use fourcc::collections::FourCCSet;
+//!
+//! let set: FourCCSet = ["avc1", "av01", "hvc1", "avc3"].into_iter().map(FourCC::from_str_const).collect();
+//! let av: Vec<String> = set.prefix("avc").map(|code| code.to_string()).collect();
+//! assert_eq!(av, ["avc1", "avc3"]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+use crate::FourCC;
+
+/// Ordering of four character codes within a sorted collection.
+pub trait KeyOrder {
+    /// Maps a code to an integer key sorting in this order.
+    fn key(code: FourCC) -> u32;
+    /// Maps an integer key back to its code.
+    fn code(key: u32) -> FourCC;
+}
+
+/// Byte-lexicographic order, equal to the order of big-endian integers and
+/// the `Ord` implementation of `FourCC`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Lexicographic;
+
+/// Numeric order of little-endian integers, sorting by the last byte first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LittleEndian;
+
+impl KeyOrder for Lexicographic {
+    fn key(code: FourCC) -> u32
+        { code.to_u32_be() }
+
+    fn code(key: u32) -> FourCC
+        { FourCC::from_u32_be(key) }
+}
+
+impl KeyOrder for LittleEndian {
+    fn key(code: FourCC) -> u32
+        { code.to_u32_le() }
+
+    fn code(key: u32) -> FourCC
+        { FourCC::from_u32_le(key) }
+}
+
+// Maps a range of codes onto a range of keys.
+fn key_bound<O: KeyOrder>(bound: Bound<&FourCC>) -> Bound<u32> {
+    match bound {
+        Bound::Included(code) => Bound::Included(O::key(*code)),
+        Bound::Excluded(code) => Bound::Excluded(O::key(*code)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+// Inclusive range of lexicographic keys sharing a prefix, `None` if the prefix
+// is longer than a code.
+fn prefix_range(prefix: &[u8]) -> Option<(Bound<u32>, Bound<u32>)> {
+    if prefix.len() > 4 { return None }
+    let (mut lo, mut hi) = ([0x00; 4], [0xff; 4]);
+    lo[..prefix.len()].copy_from_slice(prefix);
+    hi[..prefix.len()].copy_from_slice(prefix);
+    Some((Bound::Included(u32::from_be_bytes(lo)), Bound::Included(u32::from_be_bytes(hi))))
+}
+
+//------------------------------------------------------------------------------
+
+/// Sorted map keyed by four character codes.
+pub struct FourCCMap<V, O = Lexicographic> {
+    map: BTreeMap<u32, V>,
+    order: PhantomData<O>,
+}
+
+impl<V, O: KeyOrder> FourCCMap<V, O> {
+    /// Creates an empty map.
+    pub fn new() -> Self
+        { Self { map: BTreeMap::new(), order: PhantomData } }
+
+    /// Number of entries in the map.
+    pub fn len(&self) -> usize
+        { self.map.len() }
+
+    /// Checks whether the map is empty.
+    pub fn is_empty(&self) -> bool
+        { self.map.is_empty() }
+
+    /// Inserts a value, returning the previous value of the code.
+    pub fn insert(&mut self, code: FourCC, value: V) -> Option<V>
+        { self.map.insert(O::key(code), value) }
+
+    /// Removes a code, returning its value.
+    pub fn remove(&mut self, code: FourCC) -> Option<V>
+        { self.map.remove(&O::key(code)) }
+
+    /// Returns the value of a code.
+    pub fn get(&self, code: FourCC) -> Option<&V>
+        { self.map.get(&O::key(code)) }
+
+    /// Returns a mutable reference to the value of a code.
+    pub fn get_mut(&mut self, code: FourCC) -> Option<&mut V>
+        { self.map.get_mut(&O::key(code)) }
+
+    /// Checks whether the map contains a code.
+    pub fn contains_key(&self, code: FourCC) -> bool
+        { self.map.contains_key(&O::key(code)) }
+
+    /// Iterates over the entries in order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (FourCC, &V)>
+        { self.map.iter().map(|(&key, value)| (O::code(key), value)) }
+
+    /// Iterates over the codes in order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = FourCC> + '_
+        { self.map.keys().map(|&key| O::code(key)) }
+
+    /// Iterates over the values in order of their codes.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V>
+        { self.map.values() }
+
+    /// Iterates over the entries within a range of codes, where the range is
+    /// interpreted in the order of the map.
+    ///
+    /// # Panics
+    /// Panics if the range start is greater than its end in this order.
+    pub fn range<R: RangeBounds<FourCC>>(&self, range: R) -> impl DoubleEndedIterator<Item = (FourCC, &V)> + use<'_, V, O, R> {
+        let range = (key_bound::<O>(range.start_bound()), key_bound::<O>(range.end_bound()));
+        self.map.range(range).map(|(&key, value)| (O::code(key), value))
+    }
+}
+
+impl<V> FourCCMap<V, Lexicographic> {
+    /// Iterates over the entries whose codes start with a prefix.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::FourCC;
+    /// use fourcc::collections::FourCCMap;
+    ///
+    /// let mut map = FourCCMap::new();
+    /// map.insert(FourCC(*b"hvc1"), "HEVC");
+    /// map.insert(FourCC(*b"avc1"), "AVC");
+    /// map.insert(FourCC(*b"av01"), "AV1");
+    /// let names: Vec<&str> = map.prefix("av").map(|(_, &name)| name).collect();
+    /// assert_eq!(names, ["AV1", "AVC"]);
+    /// ```
+    pub fn prefix<P: AsRef<[u8]>>(&self, prefix: P) -> impl DoubleEndedIterator<Item = (FourCC, &V)> + use<'_, V, P> {
+        prefix_range(prefix.as_ref())
+            .map(|range| self.map.range(range))
+            .into_iter()
+            .flatten()
+            .map(|(&key, value)| (Lexicographic::code(key), value))
+    }
+}
+
+impl<V, O: KeyOrder> Default for FourCCMap<V, O> {
+    fn default() -> Self
+        { Self::new() }
+}
+
+impl<V: Clone, O> Clone for FourCCMap<V, O> {
+    fn clone(&self) -> Self
+        { Self { map: self.map.clone(), order: PhantomData } }
+}
+
+impl<V: PartialEq, O> PartialEq for FourCCMap<V, O> {
+    fn eq(&self, other: &Self) -> bool
+        { self.map == other.map }
+}
+
+impl<V: Eq, O> Eq for FourCCMap<V, O> {}
+
+impl<V: fmt::Debug, O: KeyOrder> fmt::Debug for FourCCMap<V, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        { f.debug_map().entries(self.iter()).finish() }
+}
+
+impl<V, O: KeyOrder> FromIterator<(FourCC, V)> for FourCCMap<V, O> {
+    fn from_iter<I: IntoIterator<Item = (FourCC, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<V, O: KeyOrder> Extend<(FourCC, V)> for FourCCMap<V, O> {
+    fn extend<I: IntoIterator<Item = (FourCC, V)>>(&mut self, iter: I)
+        { iter.into_iter().for_each(|(code, value)| { self.insert(code, value); }) }
+}
+
+//------------------------------------------------------------------------------
+
+/// Sorted set of four character codes.
+pub struct FourCCSet<O = Lexicographic> {
+    map: FourCCMap<(), O>,
+}
+
+impl<O: KeyOrder> FourCCSet<O> {
+    /// Creates an empty set.
+    pub fn new() -> Self
+        { Self { map: FourCCMap::new() } }
+
+    /// Number of codes in the set.
+    pub fn len(&self) -> usize
+        { self.map.len() }
+
+    /// Checks whether the set is empty.
+    pub fn is_empty(&self) -> bool
+        { self.map.is_empty() }
+
+    /// Adds a code, returning whether it was newly inserted.
+    pub fn insert(&mut self, code: FourCC) -> bool
+        { self.map.insert(code, ()).is_none() }
+
+    /// Removes a code, returning whether it was present.
+    pub fn remove(&mut self, code: FourCC) -> bool
+        { self.map.remove(code).is_some() }
+
+    /// Checks whether the set contains a code.
+    pub fn contains(&self, code: FourCC) -> bool
+        { self.map.contains_key(code) }
+
+    /// Iterates over the codes in order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = FourCC> + '_
+        { self.map.keys() }
+
+    /// Iterates over the codes within a range, where the range is interpreted
+    /// in the order of the set.
+    ///
+    /// # Panics
+    /// Panics if the range start is greater than its end in this order.
+    pub fn range<R: RangeBounds<FourCC>>(&self, range: R) -> impl DoubleEndedIterator<Item = FourCC> + use<'_, O, R>
+        { self.map.range(range).map(|(code, _)| code) }
+}
+
+impl FourCCSet<Lexicographic> {
+    /// Iterates over the codes starting with a prefix.
+    pub fn prefix<P: AsRef<[u8]>>(&self, prefix: P) -> impl DoubleEndedIterator<Item = FourCC> + use<'_, P>
+        { self.map.prefix(prefix).map(|(code, _)| code) }
+}
+
+impl<O: KeyOrder> Default for FourCCSet<O> {
+    fn default() -> Self
+        { Self::new() }
+}
+
+impl<O> Clone for FourCCSet<O> {
+    fn clone(&self) -> Self
+        { Self { map: self.map.clone() } }
+}
+
+impl<O> PartialEq for FourCCSet<O> {
+    fn eq(&self, other: &Self) -> bool
+        { self.map == other.map }
+}
+
+impl<O> Eq for FourCCSet<O> {}
+
+impl<O: KeyOrder> fmt::Debug for FourCCSet<O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        { f.debug_set().entries(self.iter()).finish() }
+}
+
+impl<O: KeyOrder> FromIterator<FourCC> for FourCCSet<O> {
+    fn from_iter<I: IntoIterator<Item = FourCC>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<O: KeyOrder> Extend<FourCC> for FourCCSet<O> {
+    fn extend<I: IntoIterator<Item = FourCC>>(&mut self, iter: I)
+        { iter.into_iter().for_each(|code| { self.insert(code); }) }
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(codes: &[&str]) -> Vec<FourCC>
+        { codes.iter().map(|&s| FourCC::from_str_const(s)).collect() }
+
+    #[test]
+    fn ordering() {
+        let input = codes(&["hvc1", "av01", "avc1", "AVC1"]);
+        let lexicographic: FourCCSet = input.iter().copied().collect();
+        assert_eq!(lexicographic.iter().collect::<Vec<_>>(), codes(&["AVC1", "av01", "avc1", "hvc1"]));
+
+        let little_endian: FourCCSet<LittleEndian> = input.iter().copied().collect();
+        assert_eq!(little_endian.iter().collect::<Vec<_>>(), codes(&["av01", "AVC1", "avc1", "hvc1"]));
+    }
+
+    #[test]
+    fn prefix() {
+        let set: FourCCSet = codes(&["hvc1", "av01", "avc1", "avc3", "a\u{7f}\u{7f}\u{7f}"]).into_iter().collect();
+        assert_eq!(set.prefix("avc").collect::<Vec<_>>(), codes(&["avc1", "avc3"]));
+        assert_eq!(set.prefix(b"av").count(), 3);
+        assert_eq!(set.prefix("a").count(), 4);
+        assert_eq!(set.prefix("").count(), 5);
+        assert_eq!(set.prefix("avc1").count(), 1);
+        assert_eq!(set.prefix("avc10").count(), 0);
+        assert_eq!(FourCCSet::<Lexicographic>::new().prefix([0xff]).count(), 0);
+    }
+
+    #[test]
+    fn map() {
+        let mut map: FourCCMap<u32> = FourCCMap::new();
+        assert_eq!(map.insert(FourCC(*b"data"), 1), None);
+        assert_eq!(map.insert(FourCC(*b"fmt "), 2), None);
+        assert_eq!(map.insert(FourCC(*b"data"), 3), Some(1));
+        *map.get_mut(FourCC(*b"fmt ")).unwrap() += 1;
+        assert_eq!(map.get(FourCC(*b"fmt ")), Some(&3));
+        assert_eq!(map.range(FourCC(*b"e\0\0\0")..).count(), 1);
+        assert_eq!(format!("{map:?}"), "{'data': 3, 'fmt ': 3}");
+        assert_eq!(map.remove(FourCC(*b"data")), Some(3));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn btree_key() {
+        let mut set = std::collections::BTreeSet::new();
+        set.insert(FourCC(*b"hvc1"));
+        set.insert(FourCC(*b"avc1"));
+        let mut sorted = codes(&["hvc1", "avc1"]);
+        sorted.sort();
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), sorted);
+    }
+}
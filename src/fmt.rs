@@ -0,0 +1,223 @@
+//! Escaped text representations of four character codes.
+
+use core::fmt;
+
+use crate::FourCC;
+
+/// Style used to escape bytes which are not printable ASCII.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EscapeStyle {
+    /// Printable ASCII as is, other bytes and `[` as `\xNN`, backslash as
+    /// `\\`. Used by `Display`.
+    Hex,
+    /// ASCII alphanumerics and `. -_` as is, other bytes as decimal `[N]`,
+    /// matching FFmpeg's `av_fourcc2str`. Used by alternate `Display`.
+    Bracket,
+    /// Quoted C multi-character literal with octal `\NNN` escapes, such as
+    /// `'RGB\000'`. Used by `Debug`.
+    CLiteral,
+}
+
+/// Adapter formatting a `FourCC` with an [`EscapeStyle`].
+///
+/// Formatter width, fill and alignment options are applied to the escaped
+/// text as a whole.
+///
+/// # Examples
+/// ```
+/// use fourcc::{EscapeStyle, FourCC};
+///
+/// let code = FourCC::from(0x52474200);
+/// assert_eq!(code.escape(EscapeStyle::Hex).to_string(), "RGB\\x00");
+/// assert_eq!(code.escape(EscapeStyle::Bracket).to_string(), "RGB[0]");
+/// assert_eq!(code.escape(EscapeStyle::CLiteral).to_string(), "'RGB\\000'");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Escape {
+    fourcc: FourCC,
+    style: EscapeStyle,
+}
+
+impl FourCC {
+    /// Returns an adapter formatting the code with the given escape style.
+    pub fn escape(self, style: EscapeStyle) -> Escape
+        { Escape { fourcc: self, style } }
+}
+
+impl fmt::Display for Escape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut buf = Buffer::default();
+        if self.style == EscapeStyle::CLiteral { buf.push(b'\'') }
+        for &b in &self.fourcc.0 { self.style.escape_byte(b, &mut buf) }
+        if self.style == EscapeStyle::CLiteral { buf.push(b'\'') }
+        f.pad(buf.as_str())
+    }
+}
+
+impl EscapeStyle {
+    fn escape_byte(self, b: u8, buf: &mut Buffer) {
+        match self {
+            Self::Hex => match b {
+                b'\\' => buf.extend(b"\\\\"),
+                // escaped to keep the output distinct from the bracket style
+                b'[' | 0..=0x1f | 0x7f..=0xff => buf.extend(&[b'\\', b'x', hex_digit(b >> 4), hex_digit(b & 0xf)]),
+                _ => buf.push(b),
+            },
+            Self::Bracket => match b {
+                b'.' | b' ' | b'-' | b'_' => buf.push(b),
+                _ if b.is_ascii_alphanumeric() => buf.push(b),
+                _ => {
+                    buf.push(b'[');
+                    if b >= 100 { buf.push(b'0' + b / 100) }
+                    if b >= 10 { buf.push(b'0' + b / 10 % 10) }
+                    buf.push(b'0' + b % 10);
+                    buf.push(b']');
+                }
+            },
+            Self::CLiteral => match b {
+                b'\\' | b'\'' => buf.extend(&[b'\\', b]),
+                b' '..=b'~' => buf.push(b),
+                _ => buf.extend(&[b'\\', b'0' + (b >> 6), b'0' + (b >> 3 & 7), b'0' + (b & 7)]),
+            },
+        }
+    }
+}
+
+/// Adapter formatting a `FourCC` like `Display` without trailing space padding.
+///
+/// # Examples
+/// ```
+/// use fourcc::FourCC;
+///
+/// let code = FourCC(*b"avc ");
+/// assert_eq!(format!("[{}]", code.display_trimmed()), "[avc]");
+/// assert_eq!(format!("[{:>4}]", code.display_trimmed()), "[ avc]");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrimmedDisplay {
+    fourcc: FourCC,
+}
+
+impl FourCC {
+    /// Returns an adapter formatting the code without trailing spaces.
+    pub fn display_trimmed(self) -> TrimmedDisplay
+        { TrimmedDisplay { fourcc: self } }
+}
+
+impl fmt::Display for TrimmedDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = if f.alternate() { EscapeStyle::Bracket } else { EscapeStyle::Hex };
+        let len = self.fourcc.0.iter().rposition(|&b| b != b' ').map_or(0, |i| i + 1);
+        let mut buf = Buffer::default();
+        for &b in &self.fourcc.0[..len] { style.escape_byte(b, &mut buf) }
+        f.pad(buf.as_str())
+    }
+}
+
+fn hex_digit(n: u8) -> u8
+    { b"0123456789abcdef"[n as usize] }
+
+//------------------------------------------------------------------------------
+
+/// Stack buffer large enough for the longest escaped representation.
+#[derive(Default)]
+struct Buffer {
+    bytes: [u8; 20],
+    len: usize,
+}
+
+impl Buffer {
+    fn push(&mut self, b: u8) {
+        self.bytes[self.len] = b;
+        self.len += 1;
+    }
+
+    fn extend(&mut self, bytes: &[u8])
+        { bytes.iter().for_each(|&b| self.push(b)) }
+
+    fn as_str(&self) -> &str {
+        // only ASCII is ever pushed
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+//------------------------------------------------------------------------------
+
+// Format FourCC into human readable string, escaping unprintable bytes.
+impl fmt::Display for FourCC {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = if f.alternate() { EscapeStyle::Bracket } else { EscapeStyle::Hex };
+        fmt::Display::fmt(&self.escape(style), f)
+    }
+}
+
+// Format FourCC into quoted human readable string.
+impl fmt::Debug for FourCC {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        { fmt::Display::fmt(&self.escape(EscapeStyle::CLiteral), f) }
+}
+
+// Format the big-endian integer value of the FourCC.
+impl fmt::LowerHex for FourCC {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        { fmt::LowerHex::fmt(&self.to_u32_be(), f) }
+}
+
+impl fmt::UpperHex for FourCC {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        { fmt::UpperHex::fmt(&self.to_u32_be(), f) }
+}
+
+impl fmt::Binary for FourCC {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        { fmt::Binary::fmt(&self.to_u32_be(), f) }
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_styles() {
+        let code = FourCC([0, b'\\', 0x7f, 0xff]);
+        assert_eq!(code.escape(EscapeStyle::Hex).to_string(), r"\x00\\\x7f\xff");
+        assert_eq!(code.escape(EscapeStyle::Bracket).to_string(), "[0][92][127][255]");
+        assert_eq!(code.escape(EscapeStyle::CLiteral).to_string(), r"'\000\\\177\377'");
+        assert_eq!(FourCC(*b"it's").escape(EscapeStyle::CLiteral).to_string(), r"'it\'s'");
+    }
+
+    #[test]
+    fn display_escapes() {
+        let code = FourCC::from(0x00000001);
+        assert_eq!(format!("{code}"), r"\x00\x00\x00\x01");
+        assert_eq!(format!("{code:#}"), "[0][0][0][1]");
+        assert_eq!(format!("{code:?}"), r"'\000\000\000\001'");
+        assert_eq!(format!("{:?}", FourCC(*b"fmt ")), "'fmt '");
+    }
+
+    #[test]
+    fn padding() {
+        let code = FourCC(*b"RGBA");
+        assert_eq!(format!("{code:>6}"), "  RGBA");
+        assert_eq!(format!("{code:*<6}"), "RGBA**");
+        assert_eq!(format!("{code:^8?}"), " 'RGBA' ");
+    }
+
+    #[test]
+    fn trimmed_display() {
+        assert_eq!(FourCC(*b"cvt ").display_trimmed().to_string(), "cvt");
+        assert_eq!(FourCC(*b"a b ").display_trimmed().to_string(), "a b");
+        assert_eq!(FourCC(*b"    ").display_trimmed().to_string(), "");
+        assert_eq!(format!("{:#}", FourCC([b'x', 0, b' ', b' ']).display_trimmed()), "x[0]");
+    }
+
+    #[test]
+    fn integer_formats() {
+        let code = FourCC(*b"RGBA");
+        assert_eq!(format!("{code:x}"), "52474241");
+        assert_eq!(format!("{code:#010X}"), "0x52474241");
+        assert_eq!(format!("{:b}", FourCC::from(5)), "101");
+    }
+}
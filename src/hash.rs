@@ -0,0 +1,110 @@
+//! Fast hashing of four character codes.
+//!
+//! `FourCC` hashes as a single `u32`, which [`FourCCHasher`] mixes into a
+//! 64-bit hash with one wide multiplication. The hasher is not resistant to
+//! collision attacks and should only be used with trusted or low cardinality
+//! keys, such as chunk dispatch tables.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use fourcc::FourCC;
+//! use fourcc::hash::FourCCHashMap;
+//!
+//! let mut handlers: FourCCHashMap<&str> = FourCCHashMap::default();
+//! handlers.insert(FourCC(*b"data"), "sample data");
+//! assert_eq!(handlers.get(&FourCC(*b"data")), Some(&"sample data"));
+//! # }
+//! ```
+
+use core::hash::{BuildHasherDefault, Hasher};
+
+/// Odd multiplier derived from the golden ratio.
+const K: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// Folded multiply, the high and low halves of the product mix every input bit.
+#[inline]
+pub(crate) const fn mix(x: u64) -> u64 {
+    let product = (x as u128).wrapping_mul(K as u128);
+    (product as u64) ^ (product >> 64) as u64
+}
+
+/// Hasher optimised for `FourCC` keys.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FourCCHasher {
+    hash: u64,
+}
+
+impl Hasher for FourCCHasher {
+    #[inline]
+    fn finish(&self) -> u64
+        { self.hash }
+
+    #[inline]
+    fn write_u32(&mut self, n: u32)
+        { self.hash = mix(self.hash ^ n as u64) }
+
+    fn write(&mut self, bytes: &[u8]) {
+        // generic fallback for keys which are not a single code
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.hash = mix(self.hash.rotate_left(5) ^ u64::from_le_bytes(word));
+        }
+    }
+}
+
+/// `BuildHasher` creating [`FourCCHasher`] instances.
+pub type BuildFourCCHasher = BuildHasherDefault<FourCCHasher>;
+
+/// `HashMap` keyed by `FourCC` using [`FourCCHasher`].
+#[cfg(feature = "std")]
+pub type FourCCHashMap<V> = std::collections::HashMap<crate::FourCC, V, BuildFourCCHasher>;
+
+/// `HashSet` of `FourCC` using [`FourCCHasher`].
+#[cfg(feature = "std")]
+pub type FourCCHashSet = std::collections::HashSet<crate::FourCC, BuildFourCCHasher>;
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FourCC;
+    use core::hash::{BuildHasher, Hash};
+
+    fn hash(code: &str) -> u64
+        { BuildFourCCHasher::default().hash_one(FourCC::from_str_const(code)) }
+
+    #[test]
+    fn distinct_hashes() {
+        let codes = ["RIFF", "LIST", "fmt ", "data", "avc1", "avc3", "hvc1", "hev1"];
+        let set: std::collections::HashSet<FourCC, BuildFourCCHasher> = codes.iter().map(|&c| FourCC::from_str_const(c)).collect();
+        assert_eq!(set.len(), codes.len());
+        for (i, a) in codes.iter().enumerate() {
+            for b in &codes[i + 1..] {
+                assert_ne!(hash(a), hash(b));
+            }
+        }
+    }
+
+    #[test]
+    fn hash_is_u32() {
+        let mut hasher = FourCCHasher::default();
+        FourCC(*b"RGBA").hash(&mut hasher);
+        let mut expected = FourCCHasher::default();
+        expected.write_u32(0x52474241);
+        assert_eq!(hasher.finish(), expected.finish());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn map() {
+        let set: FourCCHashSet = [FourCC(*b"fmt ")].into_iter().collect();
+        assert!(set.contains(&FourCC(*b"fmt ")));
+        let mut map: FourCCHashMap<u32> = FourCCHashMap::default();
+        map.insert(FourCC(*b"fmt "), 1);
+        map.insert(FourCC(*b"data"), 2);
+        assert_eq!(map[&FourCC(*b"data")], 2);
+    }
+}
@@ -0,0 +1,454 @@
+//! EA IFF-85 chunk reader and writer.
+//!
+//! An IFF stream is a sequence of chunks, each consisting of a `FourCC`
+//! identifier, a big-endian 32-bit payload size and the payload itself, padded
+//! to an even number of bytes. Group chunks (`FORM`, `LIST`, `CAT ` and
+//! `PROP`) begin their payload with a form type followed by nested chunks.
+//!
+//! # Examples
+//! ```
+//! use std::io::{Cursor, Read};
+//! use fourcc::iff::ChunkReader;
+//!
+//! let data = b"FORM\0\0\0\x0eTEXTCHRS\0\0\0\x02hi";
+//! let mut reader = ChunkReader::new(Cursor::new(&data[..])).unwrap();
+//! let form = reader.next_chunk().unwrap().unwrap();
+//! assert_eq!(form.form_type.unwrap(), "TEXT");
+//!
+//! let mut forms = reader.subchunks(&form).unwrap();
+//! let chrs = forms.next_chunk().unwrap().unwrap();
+//! let mut text = String::new();
+//! forms.payload(&chrs).unwrap().read_to_string(&mut text).unwrap();
+//! assert_eq!(text, "hi");
+//! ```
+//!
+//! Streams are written with a [`ChunkWriter`], which back-patches chunk sizes
+//! once each chunk is complete.
+//!
+//! ```
+//! use std::io::{Cursor, Write};
+//! use fourcc::FourCC;
+//! use fourcc::iff::{ChunkWriter, FORM};
+//!
+//! let mut writer = ChunkWriter::new(Cursor::new(Vec::new()));
+//! writer.begin_group(FORM, FourCC(*b"TEXT")).unwrap();
+//! writer.begin_chunk(FourCC(*b"CHRS")).unwrap();
+//! writer.write_all(b"hi").unwrap();
+//! writer.end_chunk().unwrap();
+//! writer.end_chunk().unwrap();
+//! let data = writer.finish().unwrap().into_inner();
+//! assert_eq!(data, b"FORM\0\0\0\x0eTEXTCHRS\0\0\0\x02hi");
+//! ```
+
+use std::io::{self, Read, Seek, SeekFrom, Take, Write};
+
+use crate::{ByteOrder, FourCC};
+
+/// Group of chunks with a form type.
+pub const FORM: FourCC = FourCC(*b"FORM");
+/// Group of forms sharing properties.
+pub const LIST: FourCC = FourCC(*b"LIST");
+/// Concatenation of groups.
+pub const CAT: FourCC = FourCC(*b"CAT ");
+/// Shared properties within a `LIST`.
+pub const PROP: FourCC = FourCC(*b"PROP");
+
+/// Size of a chunk header in bytes.
+pub const HEADER_SIZE: u64 = 8;
+
+/// Deepest nesting of groups accepted by [`ChunkReader::read_tree`].
+pub const MAX_DEPTH: usize = 256;
+
+/// Checks whether a chunk identifier denotes an IFF group chunk.
+pub fn is_group(id: FourCC) -> bool
+    { id == FORM || id == LIST || id == CAT || id == PROP }
+
+//------------------------------------------------------------------------------
+
+/// Header of a chunk within an IFF stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chunk {
+    /// Chunk identifier.
+    pub id: FourCC,
+    /// Payload size as stored in the header, excluding padding.
+    pub size: u32,
+    /// Byte offset of the chunk header within the stream.
+    pub offset: u64,
+    /// Form type of a group chunk.
+    pub form_type: Option<FourCC>,
+}
+
+impl Chunk {
+    /// Checks whether the chunk is a group containing nested chunks.
+    pub fn is_group(&self) -> bool
+        { self.form_type.is_some() }
+
+    /// Byte offset of the chunk data, following the form type of a group.
+    pub fn data_offset(&self) -> u64
+        { self.offset + HEADER_SIZE + if self.is_group() { 4 } else { 0 } }
+
+    /// Size of the chunk data, excluding the form type of a group.
+    pub fn data_size(&self) -> u64
+        { self.size as u64 - if self.is_group() { 4 } else { 0 } }
+
+    /// Byte offset immediately following the chunk and its padding.
+    pub fn end(&self) -> u64
+        { self.offset + HEADER_SIZE + self.size as u64 + (self.size & 1) as u64 }
+
+    /// Returns a reader over the chunk data within the stream it was read from.
+    pub fn payload<'a, R: Read + Seek>(&self, reader: &'a mut R) -> io::Result<Take<&'a mut R>> {
+        reader.seek(SeekFrom::Start(self.data_offset()))?;
+        Ok(reader.take(self.data_size()))
+    }
+}
+
+/// Chunk and its nested chunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Chunk header.
+    pub chunk: Chunk,
+    /// Chunks nested within a group, empty for other chunks.
+    pub children: Vec<Node>,
+}
+
+//------------------------------------------------------------------------------
+
+/// Reader yielding the chunks of an IFF stream or group.
+///
+/// Payloads are not loaded, they can be streamed with [`ChunkReader::payload`]
+/// and groups can be descended with [`ChunkReader::subchunks`].
+#[derive(Debug)]
+pub struct ChunkReader<R> {
+    reader: R,
+    next: u64,
+    end: u64,
+    order: ByteOrder,
+    is_group: fn(FourCC) -> bool,
+}
+
+impl<R: Read + Seek> ChunkReader<R> {
+    /// Creates a reader for the chunks between the current position and the
+    /// end of the stream.
+    pub fn new(reader: R) -> io::Result<Self>
+        { Self::with_format(reader, ByteOrder::Big, is_group) }
+
+    /// Creates a reader for a chunk format with the given size byte order and
+    /// group identifiers.
+    pub(crate) fn with_format(mut reader: R, order: ByteOrder, is_group: fn(FourCC) -> bool) -> io::Result<Self> {
+        let next = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        Ok(Self { reader, next, end, order, is_group })
+    }
+
+    /// Byte order of the chunk sizes.
+    pub fn byte_order(&self) -> ByteOrder
+        { self.order }
+
+    /// Reads the header of the next chunk, skipping the previous payload.
+    ///
+    /// Returns `None` at the end of the stream or group.
+    pub fn next_chunk(&mut self) -> io::Result<Option<Chunk>> {
+        if self.next >= self.end { return Ok(None) }
+        let result = self.read_chunk();
+        if result.is_err() { self.next = self.end }
+        result.map(Some)
+    }
+
+    fn read_chunk(&mut self) -> io::Result<Chunk> {
+        let offset = self.next;
+        if self.end - offset < HEADER_SIZE { return Err(invalid("truncated chunk header")) }
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let mut header = [0; 8];
+        self.reader.read_exact(&mut header)?;
+        let id = FourCC([header[0], header[1], header[2], header[3]]);
+        let size = self.order.read_u32([header[4], header[5], header[6], header[7]]);
+        if offset + HEADER_SIZE + size as u64 > self.end { return Err(invalid("chunk size exceeds its parent")) }
+        let form_type = if (self.is_group)(id) {
+            if size < 4 { return Err(invalid("group chunk is missing its form type")) }
+            let mut form_type = [0; 4];
+            self.reader.read_exact(&mut form_type)?;
+            Some(FourCC(form_type))
+        } else {
+            None
+        };
+        let chunk = Chunk { id, size, offset, form_type };
+        // a missing pad byte after the final chunk is tolerated
+        self.next = chunk.end().min(self.end);
+        Ok(chunk)
+    }
+
+    /// Returns a reader over the data of a chunk.
+    pub fn payload(&mut self, chunk: &Chunk) -> io::Result<Take<&mut R>>
+        { chunk.payload(&mut self.reader) }
+
+    /// Returns a reader over the chunks nested within a group.
+    pub fn subchunks(&mut self, chunk: &Chunk) -> io::Result<ChunkReader<&mut R>> {
+        if !chunk.is_group() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "chunk is not a group"))
+        }
+        Ok(ChunkReader {
+            reader: &mut self.reader,
+            next: chunk.data_offset(),
+            end: chunk.data_offset() + chunk.data_size(),
+            order: self.order,
+            is_group: self.is_group,
+        })
+    }
+
+    /// Reads the remaining chunks and all nested groups into a tree.
+    ///
+    /// Groups nested deeper than [`MAX_DEPTH`] are reported as invalid data.
+    pub fn read_tree(&mut self) -> io::Result<Vec<Node>>
+        { self.read_nodes(0) }
+
+    fn read_nodes(&mut self, depth: usize) -> io::Result<Vec<Node>> {
+        if depth > MAX_DEPTH { return Err(invalid("groups nested too deeply")) }
+        let mut nodes = Vec::new();
+        while let Some(chunk) = self.next_chunk()? {
+            let children = if chunk.is_group() {
+                let (next, end) = (self.next, self.end);
+                self.next = chunk.data_offset();
+                self.end = chunk.data_offset() + chunk.data_size();
+                let children = self.read_nodes(depth + 1);
+                (self.next, self.end) = (next, end);
+                children?
+            } else {
+                Vec::new()
+            };
+            nodes.push(Node { chunk, children });
+        }
+        Ok(nodes)
+    }
+
+    /// Unwraps the underlying reader.
+    pub fn into_inner(self) -> R
+        { self.reader }
+}
+
+impl<R: Read + Seek> Iterator for ChunkReader<R> {
+    type Item = io::Result<Chunk>;
+
+    fn next(&mut self) -> Option<Self::Item>
+        { self.next_chunk().transpose() }
+}
+
+//------------------------------------------------------------------------------
+
+/// Writer producing nested chunks with automatically patched sizes.
+///
+/// Chunk data is written through the [`Write`] implementation between
+/// [`ChunkWriter::begin_chunk`] and [`ChunkWriter::end_chunk`]. Sizes are
+/// written in the configured byte order and odd sized chunks are padded.
+#[derive(Debug)]
+pub struct ChunkWriter<W> {
+    writer: W,
+    order: ByteOrder,
+    open: Vec<u64>,
+}
+
+impl<W: Write + Seek> ChunkWriter<W> {
+    /// Creates a writer for an IFF stream with big-endian chunk sizes.
+    pub fn new(writer: W) -> Self
+        { Self::with_byte_order(writer, ByteOrder::Big) }
+
+    /// Creates a writer with the given chunk size byte order, use
+    /// `ByteOrder::Little` for RIFF.
+    pub fn with_byte_order(writer: W, order: ByteOrder) -> Self
+        { Self { writer, order, open: Vec::new() } }
+
+    /// Byte order of the chunk sizes.
+    pub fn byte_order(&self) -> ByteOrder
+        { self.order }
+
+    /// Number of chunks which have been started but not ended.
+    pub fn depth(&self) -> usize
+        { self.open.len() }
+
+    /// Starts a chunk, subsequent writes form its data.
+    pub fn begin_chunk(&mut self, id: FourCC) -> io::Result<()> {
+        let offset = self.writer.stream_position()?;
+        self.writer.write_all(&id.0)?;
+        self.writer.write_all(&[0; 4])?;
+        self.open.push(offset);
+        Ok(())
+    }
+
+    /// Starts a group chunk such as `FORM` or `LIST` with the given form type.
+    pub fn begin_group(&mut self, container: FourCC, form_type: FourCC) -> io::Result<()> {
+        self.begin_chunk(container)?;
+        self.writer.write_all(&form_type.0)
+    }
+
+    /// Ends the innermost chunk, writing its size and padding.
+    pub fn end_chunk(&mut self) -> io::Result<()> {
+        let offset = self.open.pop()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no chunk to end"))?;
+        let end = self.writer.stream_position()?;
+        let size = u32::try_from(end - offset - HEADER_SIZE)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "chunk exceeds 4 GiB"))?;
+        self.writer.seek(SeekFrom::Start(offset + 4))?;
+        self.writer.write_all(&self.order.write_u32(size))?;
+        self.writer.seek(SeekFrom::Start(end))?;
+        if size & 1 == 1 { self.writer.write_all(&[0])?; }
+        Ok(())
+    }
+
+    /// Writes a complete chunk with the given data.
+    pub fn write_chunk(&mut self, id: FourCC, data: &[u8]) -> io::Result<()> {
+        self.begin_chunk(id)?;
+        self.writer.write_all(data)?;
+        self.end_chunk()
+    }
+
+    /// Finishes writing, returning the underlying writer.
+    ///
+    /// Fails if any chunk has not been ended.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.open.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "unclosed chunks"))
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write + Seek> Write for ChunkWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.open.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "data written outside of a chunk"))
+        }
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()>
+        { self.writer.flush() }
+}
+
+// Malformed stream error, shared by the other stream readers.
+pub(crate) fn invalid(msg: &str) -> io::Error
+    { io::Error::new(io::ErrorKind::InvalidData, msg) }
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn ilbm() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"FORM\0\0\0\x30ILBM");
+        data.extend_from_slice(b"BMHD\0\0\0\x03abc\0");
+        data.extend_from_slice(b"LIST\0\0\0\x0eINFONAME\0\0\0\x02hi");
+        data.extend_from_slice(b"BODY\0\0\0\x02xy");
+        data
+    }
+
+    #[test]
+    fn read_chunks() {
+        let mut reader = ChunkReader::new(Cursor::new(ilbm())).unwrap();
+        let form = reader.next_chunk().unwrap().unwrap();
+        assert_eq!(form.id, FORM);
+        assert_eq!(form.form_type, Some(FourCC(*b"ILBM")));
+        assert!(reader.next_chunk().unwrap().is_none());
+
+        let chunks: Vec<Chunk> = reader.subchunks(&form).unwrap().map(Result::unwrap).collect();
+        let ids: Vec<FourCC> = chunks.iter().map(|c| c.id).collect();
+        assert_eq!(ids, [FourCC(*b"BMHD"), LIST, FourCC(*b"BODY")]);
+        assert_eq!(chunks[0].offset, 12);
+        assert_eq!(chunks[1].offset, 24);
+        assert_eq!(chunks[1].form_type, Some(FourCC(*b"INFO")));
+        assert_eq!(chunks[2].offset, 46);
+    }
+
+    #[test]
+    fn read_payload() {
+        let mut reader = ChunkReader::new(Cursor::new(ilbm())).unwrap();
+        let form = reader.next_chunk().unwrap().unwrap();
+        let mut forms = reader.subchunks(&form).unwrap();
+        let bmhd = forms.next_chunk().unwrap().unwrap();
+        let list = forms.next_chunk().unwrap().unwrap();
+
+        let mut data = Vec::new();
+        forms.payload(&bmhd).unwrap().read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"abc");
+
+        let mut info = forms.subchunks(&list).unwrap();
+        let name = info.next_chunk().unwrap().unwrap();
+        assert_eq!(name.id, "NAME");
+        data.clear();
+        info.payload(&name).unwrap().read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"hi");
+        assert!(info.next_chunk().unwrap().is_none());
+    }
+
+    #[test]
+    fn read_tree() {
+        let tree = ChunkReader::new(Cursor::new(ilbm())).unwrap().read_tree().unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].children.len(), 3);
+        assert_eq!(tree[0].children[1].children[0].chunk.id, "NAME");
+        assert!(tree[0].children[2].children.is_empty());
+    }
+
+    #[test]
+    fn write_chunks() {
+        let mut writer = ChunkWriter::new(Cursor::new(Vec::new()));
+        writer.begin_group(FORM, FourCC(*b"ILBM")).unwrap();
+        writer.write_chunk(FourCC(*b"BMHD"), b"abc").unwrap();
+        writer.begin_group(LIST, FourCC(*b"INFO")).unwrap();
+        writer.write_chunk(FourCC(*b"NAME"), b"hi").unwrap();
+        writer.end_chunk().unwrap();
+        writer.begin_chunk(FourCC(*b"BODY")).unwrap();
+        writer.write_all(b"xy").unwrap();
+        writer.end_chunk().unwrap();
+        writer.end_chunk().unwrap();
+        assert_eq!(writer.finish().unwrap().into_inner(), ilbm());
+    }
+
+    #[test]
+    fn write_little_endian() {
+        let mut writer = ChunkWriter::with_byte_order(Cursor::new(Vec::new()), ByteOrder::Little);
+        writer.begin_group(FourCC(*b"RIFF"), FourCC(*b"WAVE")).unwrap();
+        writer.write_chunk(FourCC(*b"data"), b"x").unwrap();
+        writer.end_chunk().unwrap();
+        assert_eq!(writer.finish().unwrap().into_inner(), b"RIFF\x0e\0\0\0WAVEdata\x01\0\0\0x\0");
+    }
+
+    #[test]
+    fn write_unclosed() {
+        let mut writer = ChunkWriter::new(Cursor::new(Vec::new()));
+        assert_eq!(writer.write(b"x").unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(writer.end_chunk().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        writer.begin_group(FORM, FourCC(*b"ILBM")).unwrap();
+        assert_eq!(writer.depth(), 1);
+        assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn invalid_size() {
+        let data = b"FORM\0\0\0\x10ILBM".to_vec();
+        let mut reader = ChunkReader::new(Cursor::new(data)).unwrap();
+        assert_eq!(reader.next_chunk().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn nested_too_deeply() {
+        let nested = |depth: usize| {
+            let mut data = Vec::new();
+            for level in (0..depth).rev() {
+                data.extend_from_slice(b"FORM");
+                data.extend_from_slice(&(4 + 12 * level as u32).to_be_bytes());
+                data.extend_from_slice(b"TEST");
+            }
+            data
+        };
+        let tree = ChunkReader::new(Cursor::new(nested(MAX_DEPTH))).unwrap().read_tree().unwrap();
+        assert_eq!(tree[0].chunk.size, 4 + 12 * (MAX_DEPTH as u32 - 1));
+        for depth in [MAX_DEPTH + 1, 50_000] {
+            let err = ChunkReader::new(Cursor::new(nested(depth))).unwrap().read_tree().unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
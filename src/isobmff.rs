@@ -0,0 +1,420 @@
+//! ISO base media file format box reader.
+//!
+//! MP4, QuickTime, 3GP and HEIF files are a sequence of boxes, each
+//! consisting of a big-endian 32-bit size, a `FourCC` box type and the
+//! payload. A size of `1` is followed by a 64-bit `largesize`, a size of `0`
+//! extends the box to the end of its parent or the file. Boxes of type `uuid`
+//! carry a 16 byte extended type, and full boxes begin their payload with a
+//! version and flags. Box types may contain any byte, such as QuickTime's
+//! `©nam`.
+//!
+//! Boxes listed in the known container table, see [`is_container`], are
+//! descended by [`BoxReader::read_tree`].
+//!
+//! # Examples
+//! ```
+//! use std::io::Cursor;
+//! use fourcc::isobmff;
+//!
+//! let data = b"\0\0\0\x10ftypisom\0\0\0\0\0\0\0\x14moov\0\0\0\x0cmvhd\0\0\0\0";
+//! let tree = isobmff::read_tree(Cursor::new(&data[..])).unwrap();
+//! assert_eq!(tree[1].header.box_type, "moov");
+//! let mvhd = &tree[1].children[0].header;
+//! assert_eq!(mvhd.box_type, "mvhd");
+//! assert_eq!(mvhd.full_box.unwrap().version, 0);
+//! assert_eq!(mvhd.offset, 24);
+//! ```
+
+use std::io::{self, Read, Seek, SeekFrom, Take};
+
+use crate::iff::invalid;
+use crate::FourCC;
+
+/// Box with a 16 byte extended type.
+pub const UUID: FourCC = FourCC(*b"uuid");
+/// Metadata container, a full box in ISO files but not in QuickTime.
+pub const META: FourCC = FourCC(*b"meta");
+
+/// Deepest nesting of containers accepted by [`BoxReader::read_tree`].
+pub const MAX_DEPTH: usize = 256;
+
+/// Box types whose payload is a sequence of boxes.
+const CONTAINERS: &[FourCC] = &[
+    FourCC(*b"moov"), FourCC(*b"trak"), FourCC(*b"edts"), FourCC(*b"mdia"),
+    FourCC(*b"minf"), FourCC(*b"dinf"), FourCC(*b"stbl"), FourCC(*b"mvex"),
+    FourCC(*b"moof"), FourCC(*b"traf"), FourCC(*b"mfra"), FourCC(*b"udta"),
+    FourCC(*b"tref"), FourCC(*b"meta"), FourCC(*b"ilst"), FourCC(*b"iprp"),
+    FourCC(*b"ipco"), FourCC(*b"sinf"), FourCC(*b"schi"), FourCC(*b"rinf"),
+    FourCC(*b"gmhd"), FourCC(*b"clip"), FourCC(*b"matt"), FourCC(*b"strk"),
+];
+
+/// Box types beginning with a version and flags.
+const FULL_BOXES: &[FourCC] = &[
+    FourCC(*b"mvhd"), FourCC(*b"tkhd"), FourCC(*b"mdhd"), FourCC(*b"hdlr"),
+    FourCC(*b"vmhd"), FourCC(*b"smhd"), FourCC(*b"hmhd"), FourCC(*b"nmhd"),
+    FourCC(*b"elst"), FourCC(*b"dref"), FourCC(*b"stsd"), FourCC(*b"stts"),
+    FourCC(*b"ctts"), FourCC(*b"stss"), FourCC(*b"stsz"), FourCC(*b"stz2"),
+    FourCC(*b"stsc"), FourCC(*b"stco"), FourCC(*b"co64"), FourCC(*b"meta"),
+    FourCC(*b"mehd"), FourCC(*b"trex"), FourCC(*b"mfhd"), FourCC(*b"tfhd"),
+    FourCC(*b"trun"), FourCC(*b"tfdt"), FourCC(*b"pitm"), FourCC(*b"iloc"),
+    FourCC(*b"iinf"), FourCC(*b"infe"), FourCC(*b"iref"), FourCC(*b"ipma"),
+    FourCC(*b"ispe"), FourCC(*b"pixi"), FourCC(*b"sidx"), FourCC(*b"sdtp"),
+    FourCC(*b"sbgp"), FourCC(*b"sgpd"), FourCC(*b"saiz"), FourCC(*b"saio"),
+    FourCC(*b"elng"), FourCC(*b"cprt"), FourCC(*b"kind"), FourCC(*b"mfro"),
+    FourCC(*b"tfra"), FourCC(*b"subs"), FourCC(*b"url "), FourCC(*b"urn "),
+];
+
+/// Checks whether a box type is a known container of boxes.
+pub fn is_container(box_type: FourCC) -> bool
+    { CONTAINERS.contains(&box_type) }
+
+/// Checks whether a box type is a known full box with a version and flags.
+pub fn is_full_box(box_type: FourCC) -> bool
+    { FULL_BOXES.contains(&box_type) }
+
+//------------------------------------------------------------------------------
+
+/// Version and flags of a full box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FullBox {
+    /// Version of the box format.
+    pub version: u8,
+    /// 24-bit flags.
+    pub flags: u32,
+}
+
+/// Header of a box within an ISO base media file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoxHeader {
+    /// Box type.
+    pub box_type: FourCC,
+    /// Size of the whole box including its header, with size `0` resolved
+    /// to the end of the parent.
+    pub size: u64,
+    /// Byte offset of the box within the stream.
+    pub offset: u64,
+    /// Size of the header, including any `largesize`, extended type and
+    /// full box fields.
+    pub header_size: u64,
+    /// Extended type of a `uuid` box.
+    pub extended_type: Option<[u8; 16]>,
+    /// Version and flags of a full box.
+    pub full_box: Option<FullBox>,
+}
+
+impl BoxHeader {
+    /// Checks whether the box is a known container of boxes.
+    pub fn is_container(&self) -> bool
+        { is_container(self.box_type) }
+
+    /// Byte offset of the box data, following the header.
+    pub fn data_offset(&self) -> u64
+        { self.offset + self.header_size }
+
+    /// Size of the box data, excluding the header.
+    pub fn data_size(&self) -> u64
+        { self.size - self.header_size }
+
+    /// Byte offset immediately following the box.
+    pub fn end(&self) -> u64
+        { self.offset + self.size }
+
+    /// Returns a reader over the box data within the stream it was read from.
+    pub fn payload<'a, R: Read + Seek>(&self, reader: &'a mut R) -> io::Result<Take<&'a mut R>> {
+        reader.seek(SeekFrom::Start(self.data_offset()))?;
+        Ok(reader.take(self.data_size()))
+    }
+}
+
+/// Box and its nested boxes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Box header.
+    pub header: BoxHeader,
+    /// Boxes nested within a container, empty for other boxes.
+    pub children: Vec<Node>,
+}
+
+//------------------------------------------------------------------------------
+
+/// Reader yielding the boxes of an ISO base media file or container box.
+///
+/// Payloads are not loaded, they can be streamed with [`BoxReader::payload`]
+/// and containers can be descended with [`BoxReader::children`].
+#[derive(Debug)]
+pub struct BoxReader<R> {
+    reader: R,
+    next: u64,
+    end: u64,
+}
+
+impl<R: Read + Seek> BoxReader<R> {
+    /// Creates a reader for the boxes between the current position and the
+    /// end of the stream.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let next = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        Ok(Self { reader, next, end })
+    }
+
+    /// Reads the header of the next box, skipping the previous payload.
+    ///
+    /// Returns `None` at the end of the stream or container.
+    pub fn next_box(&mut self) -> io::Result<Option<BoxHeader>> {
+        if self.next < self.end && self.at_terminator()? { self.next = self.end }
+        if self.next >= self.end { return Ok(None) }
+        let result = self.read_header();
+        self.next = match &result {
+            Ok(header) => header.end(),
+            Err(_) => self.end,
+        };
+        result.map(Some)
+    }
+
+    fn read_header(&mut self) -> io::Result<BoxHeader> {
+        let offset = self.next;
+        let available = self.end - offset;
+        if available < 8 { return Err(invalid("truncated box header")) }
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let mut header = [0; 8];
+        self.reader.read_exact(&mut header)?;
+        let box_type = FourCC([header[4], header[5], header[6], header[7]]);
+        let mut header_size = 8;
+        let size = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+            0 => available,
+            1 => {
+                if available < 16 { return Err(invalid("truncated box header")) }
+                let mut largesize = [0; 8];
+                self.reader.read_exact(&mut largesize)?;
+                header_size = 16;
+                u64::from_be_bytes(largesize)
+            }
+            size => size as u64,
+        };
+        if size > available { return Err(invalid("box size exceeds its parent")) }
+        if size < header_size { return Err(invalid("box size smaller than its header")) }
+
+        let extended_type = if box_type == UUID {
+            let mut uuid = [0; 16];
+            header_size += 16;
+            if size < header_size { return Err(invalid("box size smaller than its header")) }
+            self.reader.read_exact(&mut uuid)?;
+            Some(uuid)
+        } else {
+            None
+        };
+
+        let full_box = if is_full_box(box_type) && !self.is_quicktime_meta(box_type, size - header_size)? {
+            let mut fields = [0; 4];
+            header_size += 4;
+            if size < header_size { return Err(invalid("box size smaller than its header")) }
+            self.reader.read_exact(&mut fields)?;
+            Some(FullBox { version: fields[0], flags: u32::from_be_bytes([0, fields[1], fields[2], fields[3]]) })
+        } else {
+            None
+        };
+
+        Ok(BoxHeader { box_type, size, offset, header_size, extended_type, full_box })
+    }
+
+    // QuickTime containers such as `udta` may end with a 32-bit zero
+    // terminator, trailing zeros too short for a box header end the sequence.
+    fn at_terminator(&mut self) -> io::Result<bool> {
+        let remaining = self.end - self.next;
+        if remaining >= 8 { return Ok(false) }
+        self.reader.seek(SeekFrom::Start(self.next))?;
+        let mut trailer = [0; 8];
+        let trailer = &mut trailer[..remaining as usize];
+        self.reader.read_exact(trailer)?;
+        Ok(trailer.iter().all(|&b| b == 0))
+    }
+
+    // QuickTime `meta` boxes omit the full box fields, their first child
+    // `hdlr` then immediately follows the header.
+    fn is_quicktime_meta(&mut self, box_type: FourCC, data_size: u64) -> io::Result<bool> {
+        if box_type != META || data_size < 8 { return Ok(false) }
+        let position = self.reader.stream_position()?;
+        let mut peek = [0; 8];
+        self.reader.read_exact(&mut peek)?;
+        self.reader.seek(SeekFrom::Start(position))?;
+        Ok(peek[4..] == *b"hdlr")
+    }
+
+    /// Returns a reader over the data of a box.
+    pub fn payload(&mut self, header: &BoxHeader) -> io::Result<Take<&mut R>>
+        { header.payload(&mut self.reader) }
+
+    /// Returns a reader over the boxes nested within a box.
+    ///
+    /// Any box may be descended, whether or not it is a known container.
+    pub fn children(&mut self, header: &BoxHeader) -> io::Result<BoxReader<&mut R>>
+        { Ok(BoxReader { reader: &mut self.reader, next: header.data_offset(), end: header.end() }) }
+
+    /// Reads the remaining boxes and all nested known containers into a tree.
+    ///
+    /// Containers nested deeper than [`MAX_DEPTH`] are reported as invalid
+    /// data.
+    pub fn read_tree(&mut self) -> io::Result<Vec<Node>>
+        { self.read_nodes(0) }
+
+    fn read_nodes(&mut self, depth: usize) -> io::Result<Vec<Node>> {
+        if depth > MAX_DEPTH { return Err(invalid("containers nested too deeply")) }
+        let mut nodes = Vec::new();
+        while let Some(header) = self.next_box()? {
+            let children = if header.is_container() {
+                let (next, end) = (self.next, self.end);
+                (self.next, self.end) = (header.data_offset(), header.end());
+                let children = self.read_nodes(depth + 1);
+                (self.next, self.end) = (next, end);
+                children?
+            } else {
+                Vec::new()
+            };
+            nodes.push(Node { header, children });
+        }
+        Ok(nodes)
+    }
+
+    /// Unwraps the underlying reader.
+    pub fn into_inner(self) -> R
+        { self.reader }
+}
+
+impl<R: Read + Seek> Iterator for BoxReader<R> {
+    type Item = io::Result<BoxHeader>;
+
+    fn next(&mut self) -> Option<Self::Item>
+        { self.next_box().transpose() }
+}
+
+/// Reads an ISO base media file into a tree of boxes.
+pub fn read_tree<R: Read + Seek>(reader: R) -> io::Result<Vec<Node>>
+    { BoxReader::new(reader)?.read_tree() }
+
+//==============================================================================
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    pub(crate) fn boxed(box_type: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = (8 + payload.len() as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(box_type);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn mp4() -> Vec<u8> {
+        let hdlr = boxed(b"hdlr", b"\0\0\0\0\0\0\0\0vide");
+        let mdia = boxed(b"mdia", &hdlr);
+        let trak = boxed(b"trak", &[boxed(b"tkhd", b"\x01\0\0\x03"), mdia].concat());
+        let mut data = boxed(b"ftyp", b"isom\0\0\0\0");
+        data.extend(boxed(b"moov", &[boxed(b"mvhd", &[0; 4]), trak.clone(), trak].concat()));
+        data
+    }
+
+    #[test]
+    fn read_boxes() {
+        let mut reader = BoxReader::new(Cursor::new(mp4())).unwrap();
+        let ftyp = reader.next_box().unwrap().unwrap();
+        assert_eq!((ftyp.box_type, ftyp.offset, ftyp.size), (FourCC(*b"ftyp"), 0, 16));
+        let moov = reader.next_box().unwrap().unwrap();
+        assert!(moov.is_container());
+        assert!(reader.next_box().unwrap().is_none());
+
+        let types: Vec<FourCC> = reader.children(&moov).unwrap().map(|b| b.unwrap().box_type).collect();
+        assert_eq!(types, [FourCC(*b"mvhd"), FourCC(*b"trak"), FourCC(*b"trak")]);
+    }
+
+    #[test]
+    fn read_tree() {
+        let tree = super::read_tree(Cursor::new(mp4())).unwrap();
+        let trak = &tree[1].children[1];
+        let tkhd = trak.children[0].header;
+        assert_eq!(tkhd.full_box, Some(FullBox { version: 1, flags: 3 }));
+        assert_eq!(tkhd.header_size, 12);
+        let hdlr = trak.children[1].children[0].header;
+        assert_eq!(hdlr.box_type, "hdlr");
+        assert_eq!(hdlr.data_size(), 8);
+
+        let mut cursor = Cursor::new(mp4());
+        let mut data = Vec::new();
+        hdlr.payload(&mut cursor).unwrap().read_to_end(&mut data).unwrap();
+        assert_eq!(&data[4..], b"vide");
+    }
+
+    #[test]
+    fn large_and_open_ended() {
+        let mut data = b"\0\0\0\x01mdat\0\0\0\0\0\0\0\x12ab".to_vec();
+        data.extend_from_slice(b"\0\0\0\0free0123");
+        let boxes: Vec<BoxHeader> = BoxReader::new(Cursor::new(data)).unwrap().map(Result::unwrap).collect();
+        assert_eq!((boxes[0].size, boxes[0].header_size, boxes[0].data_size()), (18, 16, 2));
+        assert_eq!((boxes[1].offset, boxes[1].size), (18, 12));
+    }
+
+    #[test]
+    fn uuid_and_non_ascii() {
+        let mut data = boxed(b"uuid", &[7; 18]);
+        data.extend(boxed(b"udta", &boxed(&[0xa9, b'n', b'a', b'm'], b"title")));
+        let tree = super::read_tree(Cursor::new(data)).unwrap();
+        assert_eq!(tree[0].header.extended_type, Some([7; 16]));
+        assert_eq!(tree[0].header.data_size(), 2);
+        assert_eq!(tree[1].children[0].header.box_type, FourCC([0xa9, b'n', b'a', b'm']));
+    }
+
+    #[test]
+    fn meta_styles() {
+        let hdlr = boxed(b"hdlr", &[0; 12]);
+        let iso = boxed(b"meta", &[&[0; 4][..], &hdlr].concat());
+        let quicktime = boxed(b"meta", &hdlr);
+        for data in [iso, quicktime] {
+            let tree = super::read_tree(Cursor::new(data)).unwrap();
+            assert_eq!(tree[0].children.len(), 1);
+            assert_eq!(tree[0].children[0].header.box_type, "hdlr");
+        }
+    }
+
+    #[test]
+    fn zero_terminator() {
+        let udta = boxed(b"udta", &[boxed(b"\xa9nam", b"clip"), vec![0; 4]].concat());
+        let tree = super::read_tree(Cursor::new(boxed(b"moov", &udta))).unwrap();
+        let udta = &tree[0].children[0];
+        assert_eq!(udta.children.len(), 1);
+        assert_eq!(udta.children[0].header.box_type, FourCC(*b"\xa9nam"));
+
+        let udta = boxed(b"udta", &[boxed(b"\xa9nam", b"clip"), b"\0\0\0\x01".to_vec()].concat());
+        let err = super::read_tree(Cursor::new(boxed(b"moov", &udta))).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn nested_too_deeply() {
+        let nested = |depth: usize| {
+            let mut data = Vec::new();
+            for level in (1..=depth).rev() {
+                data.extend_from_slice(&(8 * level as u32).to_be_bytes());
+                data.extend_from_slice(b"moov");
+            }
+            data
+        };
+        let tree = super::read_tree(Cursor::new(nested(MAX_DEPTH))).unwrap();
+        assert_eq!(tree[0].header.size, 8 * MAX_DEPTH as u64);
+        for depth in [MAX_DEPTH + 1, 50_000] {
+            let err = super::read_tree(Cursor::new(nested(depth))).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn invalid_size() {
+        let mut reader = BoxReader::new(Cursor::new(b"\0\0\0\x20moov\0\0\0\0".to_vec())).unwrap();
+        assert_eq!(reader.next_box().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(reader.next().is_none());
+
+        for data in [&b"\0\0\0\x04free"[..], b"\0\0\0\x04mvhd", b"\0\0\0\x01mvhd\0\0\0\0\0\0\0\x0a"] {
+            let mut reader = BoxReader::new(Cursor::new(data.to_vec())).unwrap();
+            assert_eq!(reader.next_box().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
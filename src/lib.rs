@@ -1,7 +1,45 @@
 //! Implementation of FourCC struct.
-
-use std::cmp::Ordering;
-use std::hash::Hash;
+//!
+//! The crate is `no_std` compatible when the default `std` feature is
+//! disabled, which removes the stream based `iff`, `isobmff` and `riff`
+//! modules, the `png` chunk reader, the `collections` and `query` modules,
+//! the `hash::FourCCHashMap` and `hash::FourCCHashSet` aliases and
+//! `TryFrom<String>`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+mod fmt;
+mod parse;
+mod pattern;
+#[cfg(feature = "serde")]
+mod serde;
+mod validate;
+#[cfg(feature = "std")]
+pub mod collections;
+pub mod hash;
+#[cfg(feature = "std")]
+pub mod iff;
+#[cfg(feature = "std")]
+pub mod isobmff;
+pub mod phf;
+pub mod png;
+#[cfg(feature = "std")]
+pub mod query;
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod riff;
+pub mod scan;
+
+pub use fmt::{Escape, EscapeStyle, TrimmedDisplay};
+#[cfg(feature = "derive")]
+pub use fourcc_derive::FourCCEnum;
+pub use parse::{ParseError, ParseErrorKind};
+pub use pattern::FourCCPattern;
+pub use validate::{ValidationError, ValidationProfile, ValidationReason};
 
 /// Basic FourCC byte array alias.
 pub type TypeId = [u8;4];
@@ -10,38 +48,133 @@ pub type TypeId = [u8;4];
 ///
 /// [FourCC] is a method of encoding 32-bit unsigned integer values with human
 /// readable semantics. `FourCC` can be used directly as a 32-bit index in
-/// `HashMap`.
+/// `HashMap`, see `hash::FourCCHashMap` for a map with a faster hasher.
 ///
 /// [FourCC]: https://en.wikipedia.org/wiki/FourCC
-#[derive(Eq, PartialEq, PartialOrd, Hash, Clone, Copy, Default)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Default)]
 pub struct FourCC(pub TypeId);
 
+// Hash FourCC as a single 32-bit integer.
+impl Hash for FourCC {
+    fn hash<H: Hasher>(&self, state: &mut H)
+        { state.write_u32(self.to_u32_be()) }
+}
+
 //------------------------------------------------------------------------------
 
-/// Creates a new `FourCC` instance from a four character string.
+/// Creates a new `FourCC` instance from a four character byte sequence.
+impl From<&TypeId> for FourCC {
+    fn from(bytes: &TypeId) -> Self
+        { Self(*bytes) }
+}
+
+/// Creates a new `FourCC` instance from a big-endian 32-bit unsigned integer.
+///
+/// Many formats store codes as little-endian integers, see
+/// [`FourCC::from_u32_le`] and [`LeFourCC`].
+impl From<u32> for FourCC {
+    fn from(num: u32) -> Self
+        { Self(u32::to_be_bytes(num)) }
+}
+
+//------------------------------------------------------------------------------
+
+/// Error returned when a `FourCC` cannot be constructed from its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FourCCError {
+    /// Input is shorter than four bytes, contains the input length.
+    TooShort(usize),
+    /// Input is longer than four bytes, contains the input length.
+    TooLong(usize),
+    /// Byte at the given index is not ASCII.
+    NonAscii(usize),
+    /// Byte at the given index is not a graphic ASCII character.
+    NonGraphic(usize),
+}
+
+impl core::fmt::Display for FourCCError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::TooShort(len) => write!(f, "four character code too short ({len} bytes)"),
+            Self::TooLong(len) => write!(f, "four character code too long ({len} bytes)"),
+            Self::NonAscii(i) => write!(f, "non-ASCII byte at index {i}"),
+            Self::NonGraphic(i) => write!(f, "non-graphic byte at index {i}"),
+        }
+    }
+}
+
+impl core::error::Error for FourCCError {}
+
+/// Creates a new `FourCC` instance from exactly four graphic ASCII bytes.
+///
+/// # Examples
+/// ```
+/// use fourcc::{FourCC, FourCCError};
+///
+/// assert_eq!(FourCC::try_from(&b"RGBA"[..]), Ok(FourCC(*b"RGBA")));
+/// assert_eq!(FourCC::try_from(&b"RGB"[..]), Err(FourCCError::TooShort(3)));
+/// ```
+impl TryFrom<&[u8]> for FourCC {
+    type Error = FourCCError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let fourcc = match bytes.len() {
+            len @ 0..=3 => return Err(FourCCError::TooShort(len)),
+            4 => FourCC([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            len => return Err(FourCCError::TooLong(len)),
+        };
+        for (i, &b) in fourcc.0.iter().enumerate() {
+            if !b.is_ascii() { return Err(FourCCError::NonAscii(i)) }
+            if !b.is_ascii_graphic() { return Err(FourCCError::NonGraphic(i)) }
+        }
+        Ok(fourcc)
+    }
+}
+
+/// Creates a new `FourCC` instance from exactly four graphic ASCII characters.
 ///
 /// # Examples
 /// ```
-/// use fourcc::FourCC;
+/// use fourcc::{FourCC, FourCCError};
 ///
-/// let rgba = FourCC::from("RGBA");
+/// let rgba = FourCC::try_from("RGBA").unwrap();
 /// assert_eq!(rgba.0, 1380401729_u32.to_be_bytes());
+/// assert_eq!(FourCC::try_from("RG"), Err(FourCCError::TooShort(2)));
 /// ```
-impl From<&str> for FourCC {
-    fn from(s: &str) -> Self
-        { FourCC(s.as_bytes()[..4].try_into().unwrap()) }
+impl TryFrom<&str> for FourCC {
+    type Error = FourCCError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error>
+        { Self::try_from(s.as_bytes()) }
 }
 
-/// Creates a new `FourCC` instance from a four character byte sequence.
-impl From<&TypeId> for FourCC {
-    fn from(bytes: &TypeId) -> Self
-        { Self(*bytes) }
+/// Creates a new `FourCC` instance from a four character string.
+#[cfg(feature = "std")]
+impl TryFrom<String> for FourCC {
+    type Error = FourCCError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error>
+        { Self::try_from(s.as_bytes()) }
 }
 
-/// Creates a new `FourCC` instance from a 32-bit unsigned integer.
-impl From<u32> for FourCC {
-    fn from(num: u32) -> Self
-        { Self(u32::to_be_bytes(num)) }
+/// Parses a `FourCC` from any of its textual representations, see
+/// [`FourCC::parse_lenient`].
+///
+/// The parsed value is not validated, use `TryFrom<&str>` or
+/// [`FourCC::validate`] for untrusted input.
+///
+/// # Examples
+/// ```
+/// use fourcc::{FourCC, ParseErrorKind};
+///
+/// assert_eq!("RGBA".parse(), Ok(FourCC(*b"RGBA")));
+/// assert_eq!("RGBA8".parse::<FourCC>().unwrap_err().kind(), ParseErrorKind::TooLong);
+/// ```
+impl FromStr for FourCC {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+        { Self::parse_lenient(s) }
 }
 
 //------------------------------------------------------------------------------
@@ -51,6 +184,7 @@ impl From<FourCC> for TypeId {
         { fourcc.0 }
 }
 
+/// Converts a `FourCC` into a big-endian 32-bit unsigned integer.
 impl From<FourCC> for u32 {
     fn from(fourcc: FourCC) -> u32
         { u32::from_be_bytes(fourcc.0) }
@@ -65,7 +199,7 @@ impl PartialEq<&TypeId> for FourCC {
 
 impl PartialEq<&str> for FourCC {
     fn eq(&self, other: &&str) -> bool
-        { self == &FourCC::from(*other) }
+        { self.0[..] == *other.as_bytes() }
 }
 
 impl PartialEq<u32> for FourCC {
@@ -80,7 +214,7 @@ impl PartialOrd<&TypeId> for FourCC {
 
 impl PartialOrd<&str> for FourCC {
     fn partial_cmp(&self, other: &&str) -> Option<Ordering>
-        { self.partial_cmp(&FourCC::from(*other)) }
+        { self.0[..].partial_cmp(other.as_bytes()) }
 }
 
 impl PartialOrd<u32> for FourCC {
@@ -91,21 +225,360 @@ impl PartialOrd<u32> for FourCC {
 //------------------------------------------------------------------------------
 
 impl FourCC {
+    /// Creates a new `FourCC` instance from a four byte array.
+    pub const fn from_bytes(bytes: TypeId) -> Self
+        { Self(bytes) }
+
+    /// Creates a new `FourCC` instance from a big-endian 32-bit integer.
+    pub const fn from_u32_be(num: u32) -> Self
+        { Self(num.to_be_bytes()) }
+
+    /// Creates a new `FourCC` instance from a little-endian 32-bit integer.
+    pub const fn from_u32_le(num: u32) -> Self
+        { Self(num.to_le_bytes()) }
+
+    /// Creates a new `FourCC` instance from a native-endian 32-bit integer.
+    pub const fn from_u32_ne(num: u32) -> Self
+        { Self(num.to_ne_bytes()) }
+
+    /// Converts the `FourCC` into a big-endian 32-bit integer.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::FourCC;
+    ///
+    /// assert_eq!(FourCC(*b"YUY2").to_u32_be(), 0x59555932);
+    /// ```
+    pub const fn to_u32_be(self) -> u32
+        { u32::from_be_bytes(self.0) }
+
+    /// Converts the `FourCC` into a little-endian 32-bit integer, as used by
+    /// AVI, DirectShow, V4L2, DRM and DDS.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::FourCC;
+    ///
+    /// assert_eq!(FourCC(*b"YUY2").to_u32_le(), 0x32595559);
+    /// ```
+    pub const fn to_u32_le(self) -> u32
+        { u32::from_le_bytes(self.0) }
+
+    /// Converts the `FourCC` into a native-endian 32-bit integer.
+    pub const fn to_u32_ne(self) -> u32
+        { u32::from_ne_bytes(self.0) }
+
+    /// Creates a new `FourCC` instance from a four character string in a
+    /// `const` context.
+    ///
+    /// # Panics
+    /// Panics if the string is not exactly four bytes long, which fails
+    /// compilation when evaluated as a constant.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::FourCC;
+    ///
+    /// const RGBA: FourCC = FourCC::from_str_const("RGBA");
+    /// assert_eq!(RGBA, "RGBA");
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use fourcc::FourCC;
+    ///
+    /// const RGB: FourCC = FourCC::from_str_const("RGB");
+    /// ```
+    pub const fn from_str_const(s: &str) -> Self {
+        let bytes = s.as_bytes();
+        assert!(bytes.len() == 4, "four character code must be exactly four bytes");
+        Self([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
     /// Checks whether the `FourCC` value is a valid four character code.
-    pub fn is_valid(&self) -> bool
-        { self.0.iter().all(|&b| b.is_ascii_graphic()) }
+    ///
+    /// Only graphic ASCII is accepted, see [`FourCC::validate`] for format
+    /// specific rules such as space padding.
+    pub const fn is_valid(&self) -> bool {
+        let mut i = 0;
+        while i < 4 {
+            if !self.0[i].is_ascii_graphic() { return false }
+            i += 1;
+        }
+        true
+    }
+
+    /// Creates a new `FourCC` instance from one to four graphic ASCII
+    /// characters, padded on the right with spaces.
+    ///
+    /// Trailing spaces in the input are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::{FourCC, FourCCError};
+    ///
+    /// assert_eq!(FourCC::from_padded("avc"), Ok(FourCC(*b"avc ")));
+    /// assert_eq!(FourCC::from_padded("cvt "), Ok(FourCC(*b"cvt ")));
+    /// assert_eq!(FourCC::from_padded(""), Err(FourCCError::TooShort(0)));
+    /// ```
+    pub fn from_padded(s: &str) -> Result<Self, FourCCError> {
+        let bytes = s.trim_end_matches(' ').as_bytes();
+        if bytes.is_empty() { return Err(FourCCError::TooShort(0)) }
+        if bytes.len() > 4 { return Err(FourCCError::TooLong(bytes.len())) }
+        for (i, &b) in bytes.iter().enumerate() {
+            if !b.is_ascii() { return Err(FourCCError::NonAscii(i)) }
+            if !b.is_ascii_graphic() { return Err(FourCCError::NonGraphic(i)) }
+        }
+        let mut padded = [b' '; 4];
+        padded[..bytes.len()].copy_from_slice(bytes);
+        Ok(Self(padded))
+    }
+
+    /// Returns the code as a string without trailing space padding.
+    ///
+    /// Bytes which are not valid UTF-8 end the string early.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::FourCC;
+    ///
+    /// assert_eq!(FourCC(*b"avc ").trimmed(), "avc");
+    /// assert_eq!(FourCC(*b"RGBA").trimmed(), "RGBA");
+    /// ```
+    pub fn trimmed(&self) -> &str {
+        let s = match core::str::from_utf8(&self.0) {
+            Ok(s) => s,
+            Err(err) => core::str::from_utf8(&self.0[..err.valid_up_to()]).unwrap_or_default(),
+        };
+        s.trim_end_matches(' ')
+    }
+
+    /// Checks whether the code ends with space padding.
+    pub const fn is_padded(&self) -> bool
+        { self.0[3] == b' ' }
+
+    /// Returns the code with ASCII letters converted to uppercase.
+    pub const fn to_ascii_uppercase(&self) -> Self {
+        let [a, b, c, d] = self.0;
+        Self([a.to_ascii_uppercase(), b.to_ascii_uppercase(), c.to_ascii_uppercase(), d.to_ascii_uppercase()])
+    }
+
+    /// Returns the code with ASCII letters converted to lowercase.
+    pub const fn to_ascii_lowercase(&self) -> Self {
+        let [a, b, c, d] = self.0;
+        Self([a.to_ascii_lowercase(), b.to_ascii_lowercase(), c.to_ascii_lowercase(), d.to_ascii_lowercase()])
+    }
+
+    /// Checks whether two codes are equal, ignoring the case of ASCII letters.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::FourCC;
+    ///
+    /// assert!(FourCC(*b"xvid").eq_ignore_ascii_case(&FourCC(*b"XVID")));
+    /// assert!(!FourCC(*b"xvid").eq_ignore_ascii_case(&FourCC(*b"DIVX")));
+    /// ```
+    pub const fn eq_ignore_ascii_case(&self, other: &FourCC) -> bool
+        { self.to_ascii_lowercase().to_u32_be() == other.to_ascii_lowercase().to_u32_be() }
+}
+
+//------------------------------------------------------------------------------
+
+/// `FourCC` represented as a little-endian 32-bit unsigned integer.
+///
+/// # Examples
+/// ```
+/// use fourcc::{FourCC, LeFourCC};
+///
+/// let yuy2 = LeFourCC::from(0x32595559);
+/// assert_eq!(yuy2.0, FourCC(*b"YUY2"));
+/// assert_eq!(u32::from(yuy2), 0x32595559);
+/// ```
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Default, Debug)]
+#[repr(transparent)]
+pub struct LeFourCC(pub FourCC);
+
+/// `FourCC` represented as a big-endian 32-bit unsigned integer.
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Default, Debug)]
+#[repr(transparent)]
+pub struct BeFourCC(pub FourCC);
+
+impl From<u32> for LeFourCC {
+    fn from(num: u32) -> Self
+        { Self(FourCC::from_u32_le(num)) }
+}
+
+impl From<LeFourCC> for u32 {
+    fn from(fourcc: LeFourCC) -> u32
+        { fourcc.0.to_u32_le() }
+}
+
+impl From<FourCC> for LeFourCC {
+    fn from(fourcc: FourCC) -> Self
+        { Self(fourcc) }
+}
+
+impl From<LeFourCC> for FourCC {
+    fn from(fourcc: LeFourCC) -> FourCC
+        { fourcc.0 }
+}
+
+impl From<u32> for BeFourCC {
+    fn from(num: u32) -> Self
+        { Self(FourCC::from_u32_be(num)) }
+}
+
+impl From<BeFourCC> for u32 {
+    fn from(fourcc: BeFourCC) -> u32
+        { fourcc.0.to_u32_be() }
+}
+
+impl From<FourCC> for BeFourCC {
+    fn from(fourcc: FourCC) -> Self
+        { Self(fourcc) }
+}
+
+impl From<BeFourCC> for FourCC {
+    fn from(fourcc: BeFourCC) -> FourCC
+        { fourcc.0 }
+}
+
+impl core::fmt::Display for LeFourCC {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result
+        { core::fmt::Display::fmt(&self.0, f) }
+}
+
+impl core::fmt::Display for BeFourCC {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result
+        { core::fmt::Display::fmt(&self.0, f) }
+}
+
+//------------------------------------------------------------------------------
+
+/// `FourCC` compared, ordered and hashed ignoring the case of ASCII letters.
+///
+/// The original case is kept and shown by `Display` and `Debug`.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashSet;
+/// use fourcc::{CaseInsensitiveFourCC, FourCC};
+///
+/// let codecs: HashSet<CaseInsensitiveFourCC> = [FourCC(*b"XVID").into()].into();
+/// assert!(codecs.contains(&FourCC(*b"xvid").into()));
+/// ```
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(transparent)]
+pub struct CaseInsensitiveFourCC(pub FourCC);
+
+impl CaseInsensitiveFourCC {
+    /// Code with ASCII letters converted to lowercase, used for comparison.
+    pub const fn folded(&self) -> FourCC
+        { self.0.to_ascii_lowercase() }
+}
+
+impl PartialEq for CaseInsensitiveFourCC {
+    fn eq(&self, other: &Self) -> bool
+        { self.0.eq_ignore_ascii_case(&other.0) }
+}
+
+impl Eq for CaseInsensitiveFourCC {}
+
+impl PartialEq<FourCC> for CaseInsensitiveFourCC {
+    fn eq(&self, other: &FourCC) -> bool
+        { self.0.eq_ignore_ascii_case(other) }
+}
+
+impl PartialOrd for CaseInsensitiveFourCC {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering>
+        { Some(self.cmp(other)) }
+}
+
+impl Ord for CaseInsensitiveFourCC {
+    fn cmp(&self, other: &Self) -> Ordering
+        { self.folded().cmp(&other.folded()) }
+}
+
+impl Hash for CaseInsensitiveFourCC {
+    fn hash<H: Hasher>(&self, state: &mut H)
+        { self.folded().hash(state) }
+}
+
+impl From<FourCC> for CaseInsensitiveFourCC {
+    fn from(fourcc: FourCC) -> Self
+        { Self(fourcc) }
+}
+
+impl From<CaseInsensitiveFourCC> for FourCC {
+    fn from(fourcc: CaseInsensitiveFourCC) -> FourCC
+        { fourcc.0 }
 }
 
-// Format FourCC into human readable string.
-impl std::fmt::Display for FourCC {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result
-        { write!(f, "{}{}{}{}", self.0[0] as char, self.0[1] as char, self.0[2] as char, self.0[3] as char) }
+impl core::fmt::Display for CaseInsensitiveFourCC {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result
+        { core::fmt::Display::fmt(&self.0, f) }
 }
 
-// Format FourCC into quoted human readable string.
-impl std::fmt::Debug for FourCC {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result
-        { write!(f, "'{}{}{}{}'", self.0[0] as char, self.0[1] as char, self.0[2] as char, self.0[3] as char) }
+//------------------------------------------------------------------------------
+
+/// Byte order of integers stored alongside four character codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// Most significant byte first, as used by IFF and RIFX.
+    Big,
+    /// Least significant byte first, as used by RIFF.
+    Little,
+}
+
+impl ByteOrder {
+    /// Decodes a 32-bit unsigned integer in this byte order.
+    pub const fn read_u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Self::Big => u32::from_be_bytes(bytes),
+            Self::Little => u32::from_le_bytes(bytes),
+        }
+    }
+
+    /// Encodes a 32-bit unsigned integer in this byte order.
+    pub const fn write_u32(self, num: u32) -> [u8; 4] {
+        match self {
+            Self::Big => num.to_be_bytes(),
+            Self::Little => num.to_le_bytes(),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Creates a compile-time validated `FourCC` constant from a string literal.
+///
+/// Fails compilation if the string is not four graphic ASCII characters.
+///
+/// # Examples
+/// ```
+/// use fourcc::{fourcc, FourCC};
+///
+/// const RGBA: FourCC = fourcc!("RGBA");
+/// const ARGB: FourCC = fourcc!("ARGB");
+///
+/// match FourCC::from(1380401729) {
+///     RGBA => {},
+///     ARGB => unreachable!(),
+///     _ => unreachable!(),
+/// }
+/// ```
+///
+/// ```compile_fail
+/// let rgb = fourcc::fourcc!("RGB\0");
+/// ```
+#[macro_export]
+macro_rules! fourcc {
+    ($s:expr) => {
+        const {
+            let fourcc = $crate::FourCC::from_str_const($s);
+            assert!(fourcc.is_valid(), "four character code must be graphic ASCII");
+            fourcc
+        }
+    };
 }
 
 //==============================================================================
@@ -124,16 +597,87 @@ mod tests {
     }
 
     #[test]
-    fn from_string() {
-        let rgba = FourCC::from("RGBA");
+    fn try_from_str() {
+        let rgba = FourCC::try_from("RGBA").unwrap();
         assert_eq!(rgba.0, 1380401729_u32.to_be_bytes());
+        assert_eq!(FourCC::try_from(""), Err(FourCCError::TooShort(0)));
+        assert_eq!(FourCC::try_from("RG"), Err(FourCCError::TooShort(2)));
+        assert_eq!(FourCC::try_from("RGBA8"), Err(FourCCError::TooLong(5)));
+        assert_eq!(FourCC::try_from("RGé"), Err(FourCCError::NonAscii(2)));
     }
 
     #[test]
-    #[should_panic(expected = "range end index 4 out of range for slice of length 0")]
-    fn from_string_panic() {
-        let invalid = FourCC::from("");
-        assert!(!invalid.is_valid());
+    fn try_from_bytes() {
+        assert_eq!(FourCC::try_from(&b"RGBA"[..]), Ok(FourCC(*b"RGBA")));
+        assert_eq!(FourCC::try_from(&b""[..]), Err(FourCCError::TooShort(0)));
+        assert_eq!(FourCC::try_from(&b"RGBA8"[..]), Err(FourCCError::TooLong(5)));
+        assert_eq!(FourCC::try_from(&b"RG\xffA"[..]), Err(FourCCError::NonAscii(2)));
+        assert_eq!(FourCC::try_from(&b"RGB\0"[..]), Err(FourCCError::NonGraphic(3)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn try_from_string() {
+        assert_eq!(FourCC::try_from(String::from("RGBA")), Ok(FourCC(*b"RGBA")));
+        assert_eq!(FourCC::try_from(String::from("fmt ")), Err(FourCCError::NonGraphic(3)));
+        assert_eq!("RGB".parse::<FourCC>().unwrap_err().kind(), ParseErrorKind::TooShort);
+        assert_eq!("RGBA".parse::<FourCC>(), Ok(FourCC(*b"RGBA")));
+    }
+
+    #[test]
+    fn padded() {
+        assert_eq!(FourCC::from_padded("avc"), Ok(FourCC(*b"avc ")));
+        assert_eq!(FourCC::from_padded("DX50"), Ok(FourCC(*b"DX50")));
+        assert_eq!(FourCC::from_padded("x  "), Ok(FourCC(*b"x   ")));
+        assert_eq!(FourCC::from_padded("   "), Err(FourCCError::TooShort(0)));
+        assert_eq!(FourCC::from_padded("mp4a1"), Err(FourCCError::TooLong(5)));
+        assert_eq!(FourCC::from_padded("a b"), Err(FourCCError::NonGraphic(1)));
+        assert_eq!(FourCC::from_padded("é"), Err(FourCCError::NonAscii(0)));
+        assert_eq!(FourCC::from_padded("cvt").unwrap(), b"cvt ");
+
+        assert!(FourCC(*b"cvt ").is_padded());
+        assert!(!FourCC(*b"mp4a").is_padded());
+        assert_eq!(FourCC(*b"cvt ").trimmed(), "cvt");
+        assert_eq!(FourCC(*b"    ").trimmed(), "");
+        assert_eq!(FourCC([b'a', 0xff, b' ', b' ']).trimmed(), "a");
+    }
+
+    #[test]
+    fn ascii_case() {
+        let code = FourCC([b'x', b'V', 0xe9, b'1']);
+        assert_eq!(code.to_ascii_uppercase(), FourCC([b'X', b'V', 0xe9, b'1']));
+        assert_eq!(code.to_ascii_lowercase(), FourCC([b'x', b'v', 0xe9, b'1']));
+        assert!(code.eq_ignore_ascii_case(&FourCC([b'X', b'v', 0xe9, b'1'])));
+        assert!(!code.eq_ignore_ascii_case(&FourCC([b'X', b'v', 0xc9, b'1'])));
+        assert!(!FourCC(*b"@AVC").eq_ignore_ascii_case(&FourCC(*b"`AVC")));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        use std::collections::HashMap;
+        let upper = CaseInsensitiveFourCC(FourCC(*b"XVID"));
+        let lower = CaseInsensitiveFourCC(FourCC(*b"xvid"));
+        assert_eq!(upper, lower);
+        assert_eq!(upper, FourCC(*b"xViD"));
+        assert_eq!(upper.cmp(&lower), Ordering::Equal);
+        assert!(CaseInsensitiveFourCC(FourCC(*b"DIVX")) < lower);
+
+        let mut map = HashMap::new();
+        map.insert(upper, 1);
+        assert_eq!(map.get(&lower), Some(&1));
+        assert_eq!(lower.to_string(), "xvid");
+    }
+
+    #[test]
+    fn from_const() {
+        const RGBA: FourCC = fourcc!("RGBA");
+        const TABLE: [FourCC; 3] = [
+            FourCC::from_bytes(*b"RGBA"),
+            FourCC::from_u32_be(1380401729),
+            FourCC::from_u32_le(1094862674),
+        ];
+        assert!(TABLE.iter().all(|&f| f == RGBA));
+        assert_eq!(FourCC::from_str_const("ARGB"), "ARGB");
     }
 
     #[test]
@@ -142,21 +686,36 @@ mod tests {
         assert_eq!(rgba.0, 1380401729_u32.to_be_bytes());
     }
 
+    #[test]
+    fn endianness() {
+        let yuyv = FourCC(*b"YUYV");
+        assert_eq!(FourCC::from_u32_le(0x56595559), yuyv);
+        assert_eq!(FourCC::from_u32_be(0x59555956), yuyv);
+        assert_eq!(FourCC::from_u32_ne(yuyv.to_u32_ne()), yuyv);
+        assert_eq!(yuyv.to_u32_le(), 0x56595559);
+        assert_eq!(yuyv.to_u32_be(), 0x59555956);
+        assert_eq!(LeFourCC::from(0x56595559).0, yuyv);
+        assert_eq!(BeFourCC::from(0x59555956).0, yuyv);
+        assert_eq!(u32::from(LeFourCC::from(yuyv)), 0x56595559);
+        assert_eq!(u32::from(BeFourCC::from(yuyv)), 0x59555956);
+        assert_eq!(LeFourCC::from(0x56595559).to_string(), "YUYV");
+    }
+
     #[test]
     fn into_bytes() {
-        let rgba = FourCC::from("RGBA");
+        let rgba = FourCC(*b"RGBA");
         assert_eq!(<TypeId>::from(rgba), 1380401729_u32.to_be_bytes());
     }
 
     #[test]
     fn to_string() {
-        let rgba = FourCC::from("RGBA");
+        let rgba = FourCC(*b"RGBA");
         assert_eq!(rgba.to_string(), "RGBA");
     }
 
     #[test]
     fn into_u32() {
-        let rgba = FourCC::from("RGBA");
+        let rgba = FourCC(*b"RGBA");
         assert_eq!(u32::from(rgba), 1380401729);
     }
 
@@ -164,15 +723,15 @@ mod tests {
     fn hash_map_key() {
         use std::collections::HashMap;
         let mut map: HashMap<FourCC, &str> = HashMap::new();
-        let rgba = FourCC::from("RGBA");
+        let rgba = FourCC(*b"RGBA");
         map.insert(rgba, "RGBA colour format");
         assert_eq!(map.get(&rgba), Some(&"RGBA colour format"));
     }
 
     #[test]
     fn equality() {
-        let rgba = FourCC::from("RGBA");
-        let argb = FourCC::from("ARGB");
+        let rgba = FourCC(*b"RGBA");
+        let argb = FourCC(*b"ARGB");
         assert_eq!(rgba, rgba);
         assert_eq!(rgba, 1380401729_u32);
         assert_eq!(rgba, b"RGBA");
@@ -189,16 +748,16 @@ mod tests {
 
     #[test]
     fn validate() {
-        let rgba = FourCC::from("RGBA");
+        let rgba = FourCC(*b"RGBA");
         assert!(rgba.is_valid());
 
-        let invalid = FourCC::from("\0\x01\x02\x03");
+        let invalid = FourCC(*b"\0\x01\x02\x03");
         assert!(!invalid.is_valid());
     }
 
     #[test]
     fn fmt() {
-        let rgba = FourCC::from("RGBA");
+        let rgba = FourCC(*b"RGBA");
         let output = format!("{}", rgba);
         assert_eq!(output, "RGBA");
     }
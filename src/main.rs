@@ -0,0 +1,141 @@
+//! Command line tool for inspecting four character codes and chunk files.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::process::ExitCode;
+
+use fourcc::iff::{self, ChunkReader, Node};
+use fourcc::query::Query;
+use fourcc::{isobmff, registry, riff, FourCC};
+
+const USAGE: &str = "\
+Usage: fourcc <command> [args]
+
+Commands:
+  show <code>        Print the integer values and description of a code
+  from [--le] <int>  Print the code of a decimal or 0x prefixed integer
+  tree <file>        Print the chunk tree of an IFF, RIFF, RIFX or ISO BMFF file
+  query <file> <path>
+                     Print the chunks matching a path such as moov/trak[0]/mdia";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["show", code] => show(code),
+        ["from", num] => from(num, false),
+        ["from", "--le", num] => from(num, true),
+        ["tree", path] => tree(path),
+        ["query", path, selector] => query(path, selector),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2)
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("fourcc: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+fn show(code: &str) -> Result<(), String> {
+    let fourcc: FourCC = code.parse().map_err(|err| format!("invalid code '{code}': {err}"))?;
+    println!("code:          {fourcc:?}");
+    println!("big-endian:    {0:>10}  0x{0:08x}", fourcc.to_u32_be());
+    println!("little-endian: {0:>10}  0x{0:08x}", fourcc.to_u32_le());
+    for info in registry::find(fourcc) {
+        println!("{:?}: {} - {} <{}>", info.domain, info.name, info.description, info.reference);
+    }
+    Ok(())
+}
+
+fn from(num: &str, little_endian: bool) -> Result<(), String> {
+    let parsed = match num.strip_prefix("0x").or_else(|| num.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => num.parse(),
+    };
+    let num = parsed.map_err(|err| format!("invalid integer '{num}': {err}"))?;
+    let fourcc = if little_endian { FourCC::from_u32_le(num) } else { FourCC::from_u32_be(num) };
+    println!("{fourcc:?}");
+    Ok(())
+}
+
+/// Tree of an IFF style chunk file or an ISO base media file.
+enum Tree {
+    Chunks(Vec<Node>),
+    Boxes(Vec<isobmff::Node>),
+}
+
+fn read_tree(path: &str) -> io::Result<Tree> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut header = [0; 8];
+    file.read_exact(&mut header)?;
+    file.seek(SeekFrom::Start(0))?;
+    let id = FourCC([header[0], header[1], header[2], header[3]]);
+    let box_type = FourCC([header[4], header[5], header[6], header[7]]);
+    match id {
+        riff::RIFF | riff::RIFX => riff::read_tree(file).map(Tree::Chunks),
+        id if iff::is_group(id) => ChunkReader::new(file)?.read_tree().map(Tree::Chunks),
+        _ if box_type == "ftyp" || box_type == "moov" || box_type == "wide" => isobmff::read_tree(file).map(Tree::Boxes),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "unrecognised chunk file")),
+    }
+}
+
+fn tree(path: &str) -> Result<(), String> {
+    let tree = read_tree(path).map_err(|err| format!("{path}: {err}"))?;
+    match tree {
+        Tree::Chunks(nodes) => {
+            println!("{:>10} {:>10}  chunk", "offset", "size");
+            print_nodes(&nodes);
+        }
+        Tree::Boxes(nodes) => {
+            println!("{:>10} {:>10}  box", "offset", "size");
+            print_boxes(&nodes);
+        }
+    }
+    Ok(())
+}
+
+fn query(path: &str, query: &str) -> Result<(), String> {
+    let query: Query = query.parse().map_err(|err| format!("invalid query '{query}': {err}"))?;
+    let tree = read_tree(path).map_err(|err| format!("{path}: {err}"))?;
+    println!("{:>10} {:>10}  chunk", "offset", "size");
+    match tree {
+        Tree::Chunks(nodes) => for node in query.select(&nodes) {
+            let chunk = &node.chunk;
+            let form_type = chunk.form_type.map(|t| format!(" {t:?}")).unwrap_or_default();
+            println!("{:>10} {:>10}  {:?}{}", chunk.offset, chunk.size, chunk.id, form_type);
+        },
+        Tree::Boxes(nodes) => for node in query.select(&nodes) {
+            let header = &node.header;
+            println!("{:>10} {:>10}  {:?}", header.offset, header.size, header.box_type);
+        },
+    }
+    Ok(())
+}
+
+fn print_nodes(nodes: &[Node]) {
+    let mut stack = vec![nodes.iter()];
+    while let Some(siblings) = stack.last_mut() {
+        let Some(node) = siblings.next() else { stack.pop(); continue };
+        let chunk = &node.chunk;
+        let form_type = chunk.form_type.map(|t| format!(" {t:?}")).unwrap_or_default();
+        println!("{:>10} {:>10}  {:indent$}{:?}{}", chunk.offset, chunk.size, "", chunk.id, form_type, indent = (stack.len() - 1) * 2);
+        stack.push(node.children.iter());
+    }
+}
+
+fn print_boxes(nodes: &[isobmff::Node]) {
+    let mut stack = vec![nodes.iter()];
+    while let Some(siblings) = stack.last_mut() {
+        let Some(node) = siblings.next() else { stack.pop(); continue };
+        let header = &node.header;
+        println!("{:>10} {:>10}  {:indent$}{:?}", header.offset, header.size, "", header.box_type, indent = (stack.len() - 1) * 2);
+        stack.push(node.children.iter());
+    }
+}
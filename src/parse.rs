@@ -0,0 +1,372 @@
+//! Lenient parsing of textual four character code representations.
+
+use core::fmt;
+
+use crate::FourCC;
+
+/// Kind of error encountered while parsing a textual `FourCC`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// Input is empty.
+    Empty,
+    /// Input describes fewer than four bytes.
+    TooShort,
+    /// Input describes more than four bytes.
+    TooLong,
+    /// Character is not valid at this position.
+    InvalidChar,
+    /// Unknown or malformed escape sequence.
+    InvalidEscape,
+    /// Number is malformed or out of range.
+    InvalidNumber,
+    /// Quote, bracket or parenthesis is not closed.
+    Unterminated,
+}
+
+/// Error returned when a textual `FourCC` cannot be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    column: usize,
+    kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub(crate) fn new(column: usize, kind: ParseErrorKind) -> Self
+        { Self { column, kind } }
+
+    /// Zero-based byte column of the offending input.
+    pub fn column(&self) -> usize
+        { self.column }
+
+    /// Kind of error.
+    pub fn kind(&self) -> ParseErrorKind
+        { self.kind }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self.kind {
+            ParseErrorKind::Empty => "empty four character code",
+            ParseErrorKind::TooShort => "four character code too short",
+            ParseErrorKind::TooLong => "four character code too long",
+            ParseErrorKind::InvalidChar => "invalid character",
+            ParseErrorKind::InvalidEscape => "invalid escape sequence",
+            ParseErrorKind::InvalidNumber => "invalid number",
+            ParseErrorKind::Unterminated => "unterminated sequence",
+        };
+        write!(f, "{msg} at column {}", self.column)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+//------------------------------------------------------------------------------
+
+// C macros taking the four characters of a code in memory order.
+const MACROS: [&str; 4] = ["MAKEFOURCC", "MKTAG", "v4l2_fourcc", "fourcc_code"];
+
+impl FourCC {
+    /// Parses a `FourCC` from any of its common textual representations.
+    ///
+    /// Accepted forms are:
+    /// * four printable ASCII characters without backslashes, `RGBA`
+    /// * `\xNN` and `\\` escapes as produced by `Display`, `RGB\x00`
+    /// * decimal bracket escapes as produced by alternate `Display`, `RGB[0]`
+    /// * quoted C multi-character literals as produced by `Debug`, `'RGB\000'`
+    /// * big-endian hexadecimal integers of exactly eight digits, `0x52474200`
+    /// * C macro invocations, `MAKEFOURCC('R','G','B',0)`, also `MKTAG`,
+    ///   `v4l2_fourcc` and `fourcc_code`
+    ///
+    /// The parsed value is not validated, see [`FourCC::is_valid`].
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::FourCC;
+    ///
+    /// let rgba = FourCC(*b"RGBA");
+    /// assert_eq!(FourCC::parse_lenient("0x52474241"), Ok(rgba));
+    /// assert_eq!(FourCC::parse_lenient("'RGBA'"), Ok(rgba));
+    /// assert_eq!(FourCC::parse_lenient("[82][71][66][65]"), Ok(rgba));
+    /// assert_eq!(FourCC::parse_lenient("MAKEFOURCC('R','G','B','A')"), Ok(rgba));
+    /// assert_eq!(FourCC::parse_lenient(r"RGB\x00"), Ok(FourCC(*b"RGB\0")));
+    /// assert_eq!(FourCC::parse_lenient(r"RGB\q").unwrap_err().column(), 3);
+    /// ```
+    pub fn parse_lenient(s: &str) -> Result<FourCC, ParseError> {
+        let bytes = s.as_bytes();
+        if bytes.is_empty() { return Err(ParseError { column: 0, kind: ParseErrorKind::Empty }) }
+        // no escaped code is `0x` followed by eight hex digits, shorter forms
+        // such as `0x12` are codes
+        let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"))
+            .filter(|hex| hex.len() == 8 && hex.bytes().all(|b| b.is_ascii_hexdigit()));
+        if let Some(num) = hex.and_then(|hex| u32::from_str_radix(hex, 16).ok()) {
+            return Ok(FourCC::from_u32_be(num))
+        }
+        if bytes.len() == 4 && bytes.iter().all(|&b| is_printable(b) && b != b'\\') {
+            return Ok(FourCC([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        }
+        let mut cursor = Cursor { s: bytes, pos: 0 };
+        if let Some(name) = MACROS.iter().find(|name| s.starts_with(*name)) {
+            cursor.pos = name.len();
+            return cursor.parse_macro()
+        }
+        if bytes.len() >= 2 && bytes[0] == b'\'' && bytes[bytes.len() - 1] == b'\'' {
+            // a quoted Display representation is not a C literal
+            return cursor.parse_quoted().or_else(|err| {
+                Cursor { s: bytes, pos: 0 }.parse_escaped().map_err(|_| err)
+            })
+        }
+        cursor.parse_escaped()
+    }
+}
+
+fn is_printable(b: u8) -> bool
+    { (b' '..=b'~').contains(&b) }
+
+//------------------------------------------------------------------------------
+
+/// Accumulates the bytes of a code.
+#[derive(Default)]
+struct Output {
+    bytes: [u8; 4],
+    len: usize,
+}
+
+impl Output {
+    fn push(&mut self, b: u8, column: usize) -> Result<(), ParseError> {
+        if self.len == 4 { return Err(ParseError { column, kind: ParseErrorKind::TooLong }) }
+        self.bytes[self.len] = b;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finish(self, column: usize) -> Result<FourCC, ParseError> {
+        if self.len < 4 { return Err(ParseError { column, kind: ParseErrorKind::TooShort }) }
+        Ok(FourCC(self.bytes))
+    }
+}
+
+/// Position within the input being parsed.
+struct Cursor<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self) -> Option<u8>
+        { self.s.get(self.pos).copied() }
+
+    fn error(&self, column: usize, kind: ParseErrorKind) -> ParseError
+        { ParseError { column: column.min(self.s.len()), kind } }
+
+    fn bump(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == b => { self.pos += 1; Ok(()) },
+            Some(_) => Err(self.error(self.pos, ParseErrorKind::InvalidChar)),
+            None => Err(self.error(self.pos, ParseErrorKind::Unterminated)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) { self.pos += 1 }
+    }
+
+    // Reads up to `max` digits of the given radix, returning their value.
+    fn digits(&mut self, radix: u32, max: usize) -> Option<u32> {
+        let start = self.pos;
+        let mut value = 0u32;
+        while self.pos - start < max {
+            match self.peek().and_then(|b| (b as char).to_digit(radix)) {
+                Some(d) => { value = value.saturating_mul(radix).saturating_add(d); self.pos += 1 },
+                None => break,
+            }
+        }
+        (self.pos > start).then_some(value)
+    }
+
+    fn byte_value(&self, value: Option<u32>, column: usize) -> Result<u8, ParseError> {
+        value.and_then(|v| u8::try_from(v).ok())
+            .ok_or_else(|| self.error(column, ParseErrorKind::InvalidNumber))
+    }
+
+    // Parses `\xNN`, `\\` and `[N]` escaped text spanning the whole input.
+    fn parse_escaped(&mut self) -> Result<FourCC, ParseError> {
+        let mut out = Output::default();
+        while let Some(b) = self.peek() {
+            let column = self.pos;
+            self.pos += 1;
+            let b = match b {
+                b'\\' => match self.bump() {
+                    Some(b'\\') => b'\\',
+                    Some(b'x') => {
+                        let value = self.digits(16, 2);
+                        if self.pos - column != 4 { return Err(self.error(column, ParseErrorKind::InvalidEscape)) }
+                        self.byte_value(value, column)?
+                    },
+                    _ => return Err(self.error(column, ParseErrorKind::InvalidEscape)),
+                },
+                b'[' => {
+                    let value = self.digits(10, 3);
+                    let value = self.byte_value(value, column + 1)?;
+                    if self.peek() != Some(b']') { return Err(self.error(column, ParseErrorKind::Unterminated)) }
+                    self.pos += 1;
+                    value
+                },
+                b if is_printable(b) => b,
+                _ => return Err(self.error(column, ParseErrorKind::InvalidChar)),
+            };
+            out.push(b, column)?;
+        }
+        out.finish(self.pos)
+    }
+
+    // Parses a single character of a C character literal.
+    fn c_char(&mut self) -> Result<u8, ParseError> {
+        let column = self.pos;
+        match self.bump() {
+            Some(b'\\') => match self.bump() {
+                Some(b'x') => {
+                    let value = self.digits(16, 2);
+                    self.byte_value(value, column)
+                },
+                Some(b'0'..=b'7') => {
+                    self.pos -= 1;
+                    let value = self.digits(8, 3);
+                    self.byte_value(value, column)
+                },
+                Some(b'n') => Ok(b'\n'),
+                Some(b'r') => Ok(b'\r'),
+                Some(b't') => Ok(b'\t'),
+                Some(b @ (b'\\' | b'\'' | b'"')) => Ok(b),
+                _ => Err(self.error(column, ParseErrorKind::InvalidEscape)),
+            },
+            Some(b) if is_printable(b) && b != b'\'' => Ok(b),
+            Some(_) => Err(self.error(column, ParseErrorKind::InvalidChar)),
+            None => Err(self.error(column, ParseErrorKind::Unterminated)),
+        }
+    }
+
+    // Parses a quoted C multi-character literal spanning the whole input.
+    fn parse_quoted(&mut self) -> Result<FourCC, ParseError> {
+        self.expect(b'\'')?;
+        let end = self.s.len() - 1;
+        let mut out = Output::default();
+        while self.pos < end {
+            let column = self.pos;
+            let b = self.c_char()?;
+            if self.pos > end { return Err(self.error(end, ParseErrorKind::Unterminated)) }
+            out.push(b, column)?;
+        }
+        out.finish(end)
+    }
+
+    // Parses the parenthesised arguments of a C macro.
+    fn parse_macro(&mut self) -> Result<FourCC, ParseError> {
+        let mut out = Output::default();
+        self.skip_whitespace();
+        self.expect(b'(')?;
+        for i in 0..4 {
+            self.skip_whitespace();
+            let column = self.pos;
+            let b = match self.peek() {
+                Some(b'\'') => {
+                    self.pos += 1;
+                    let b = self.c_char()?;
+                    self.expect(b'\'')?;
+                    b
+                },
+                Some(b'0') if matches!(self.s.get(self.pos + 1), Some(b'x' | b'X')) => {
+                    self.pos += 2;
+                    let value = self.digits(16, 8);
+                    self.byte_value(value, column)?
+                },
+                Some(b'0'..=b'9') => {
+                    let value = self.digits(10, 10);
+                    self.byte_value(value, column)?
+                },
+                Some(_) => return Err(self.error(column, ParseErrorKind::InvalidChar)),
+                None => return Err(self.error(column, ParseErrorKind::Unterminated)),
+            };
+            out.push(b, column)?;
+            self.skip_whitespace();
+            if i < 3 {
+                if self.peek() == Some(b')') { return Err(self.error(self.pos, ParseErrorKind::TooShort)) }
+                self.expect(b',')?;
+            }
+        }
+        if self.peek() == Some(b',') { return Err(self.error(self.pos, ParseErrorKind::TooLong)) }
+        self.expect(b')')?;
+        self.skip_whitespace();
+        if self.pos < self.s.len() { return Err(self.error(self.pos, ParseErrorKind::InvalidChar)) }
+        out.finish(self.pos)
+    }
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EscapeStyle;
+
+    fn error(s: &str) -> (usize, ParseErrorKind) {
+        let err = FourCC::parse_lenient(s).unwrap_err();
+        (err.column(), err.kind())
+    }
+
+    #[test]
+    fn parse_forms() {
+        let rgba = FourCC(*b"RGBA");
+        for s in [
+            "RGBA", "0x52474241", "0X52474241", "'RGBA'", r"RG\x42A", "[82][71][66][65]", "RG[66]A",
+            "MAKEFOURCC('R','G','B','A')", "MKTAG('R', 'G', 'B', 'A')", "v4l2_fourcc('R', 'G', 66, 0x41)",
+        ] {
+            assert_eq!(FourCC::parse_lenient(s), Ok(rgba), "{s}");
+            assert_eq!(s.parse(), Ok(rgba), "{s}");
+        }
+        assert_eq!(FourCC::parse_lenient("0x00000001"), Ok(FourCC([0, 0, 0, 1])));
+        assert_eq!(FourCC::parse_lenient("0x12"), Ok(FourCC(*b"0x12")));
+        assert_eq!(FourCC::parse_lenient(r"0x\x00\x01"), Ok(FourCC([b'0', b'x', 0, 1])));
+        assert_eq!(FourCC::parse_lenient("fmt "), Ok(FourCC(*b"fmt ")));
+        assert_eq!(FourCC::parse_lenient("'AB'"), Ok(FourCC(*b"'AB'")));
+        assert_eq!(FourCC::parse_lenient(r"'\0\0\0\1'"), Ok(FourCC([0, 0, 0, 1])));
+    }
+
+    #[test]
+    fn round_trip() {
+        for code in [
+            *b"RGBA", *b"fmt ", [0, 1, 2, 3], *b"[0]A", *b"\\x[]", [b'\'', 1, 2, b'\''], [0xff, b'\'', b'\\', 0x80],
+            *b"0x12", [b'0', b'x', 0, 1],
+        ] {
+            let code = FourCC(code);
+            for style in [EscapeStyle::Hex, EscapeStyle::Bracket, EscapeStyle::CLiteral] {
+                let s = code.escape(style).to_string();
+                assert_eq!(FourCC::parse_lenient(&s), Ok(code), "{s}");
+            }
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(error(""), (0, ParseErrorKind::Empty));
+        assert_eq!(error("RGB"), (3, ParseErrorKind::TooShort));
+        assert_eq!(error("RGBA8"), (4, ParseErrorKind::TooLong));
+        assert_eq!(error(r"RG\yA"), (2, ParseErrorKind::InvalidEscape));
+        assert_eq!(error(r"RGB\x0"), (3, ParseErrorKind::InvalidEscape));
+        assert_eq!(error("RGB[256]"), (4, ParseErrorKind::InvalidNumber));
+        assert_eq!(error("RGB[0"), (3, ParseErrorKind::Unterminated));
+        assert_eq!(error("RGB\u{e9}"), (3, ParseErrorKind::InvalidChar));
+        assert_eq!(error("0x1"), (3, ParseErrorKind::TooShort));
+        assert_eq!(error("0x1234567"), (4, ParseErrorKind::TooLong));
+        assert_eq!(error("0x1234567890"), (4, ParseErrorKind::TooLong));
+        assert_eq!(error("0xRGBA"), (4, ParseErrorKind::TooLong));
+        assert_eq!(error("MAKEFOURCC('R','G','B')"), (22, ParseErrorKind::TooShort));
+        assert_eq!(error("MAKEFOURCC('R','G','B','A'"), (26, ParseErrorKind::Unterminated));
+        assert_eq!(error("MKTAG('R','G','B',256)"), (18, ParseErrorKind::InvalidNumber));
+    }
+}
@@ -0,0 +1,258 @@
+//! Wildcard patterns matching families of four character codes.
+
+use core::str::FromStr;
+
+use crate::parse::{ParseError, ParseErrorKind};
+use crate::FourCC;
+
+/// Set of byte values allowed at one position of a pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ByteSet([u128; 2]);
+
+impl ByteSet {
+    const EMPTY: Self = Self([0; 2]);
+    const ALL: Self = Self([u128::MAX; 2]);
+
+    fn insert(&mut self, b: u8)
+        { self.0[(b >> 7) as usize] |= 1 << (b & 0x7f) }
+
+    fn contains(&self, b: u8) -> bool
+        { self.0[(b >> 7) as usize] & 1 << (b & 0x7f) != 0 }
+
+    fn len(&self) -> u32
+        { self.0[0].count_ones() + self.0[1].count_ones() }
+
+    fn invert(&mut self)
+        { self.0 = [!self.0[0], !self.0[1]] }
+
+    // Returns the mask of bits shared by every member and their value, and
+    // whether the mask alone describes the set.
+    fn mask_value(&self) -> (u8, u8, bool) {
+        let mut members = (0..=255).filter(|&b| self.contains(b));
+        let Some(first) = members.next() else { return (0xff, 0, false) };
+        let varying = members.fold(0, |varying, b| varying | (b ^ first));
+        let mask = !varying;
+        (mask, first & mask, self.len() == 1 << varying.count_ones())
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Pattern matching four character codes with wildcards and character
+/// classes.
+///
+/// Each of the four positions is one of:
+/// * a character up to `U+00FF`, matching that byte
+/// * `?`, matching any byte
+/// * a class such as `[12]`, `[a-f]` or `[!0-9]`, matching any listed byte
+///   or, with `!`, any byte not listed
+/// * `\` followed by a character, matching that character literally
+///
+/// Patterns are compiled to a mask and value on the big-endian integer value
+/// of a code. Matching is a single AND and compare when every position is
+/// described by its mask, such as `?` or `[13]`, other classes are also
+/// checked against a per-position table.
+///
+/// # Examples
+/// ```
+/// use fourcc::{FourCC, FourCCPattern};
+///
+/// let hevc: FourCCPattern = "h[ve][cv][1c]".parse().unwrap();
+/// assert!(hevc.matches(FourCC(*b"hvc1")));
+/// assert!(hevc.matches(FourCC(*b"hev1")));
+///
+/// let avc = FourCCPattern::parse_ignore_case("avc?").unwrap();
+/// assert!(avc.matches(FourCC(*b"AVC1")));
+/// assert_eq!(avc.mask_value(), Some((0xdfdfdf00, 0x41564300)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FourCCPattern {
+    mask: u32,
+    value: u32,
+    classes: Option<[ByteSet; 4]>,
+}
+
+impl FourCCPattern {
+    /// Parses a case sensitive pattern.
+    pub fn parse(s: &str) -> Result<Self, ParseError>
+        { Self::compile(s, false) }
+
+    /// Parses a pattern matching ASCII letters in either case.
+    pub fn parse_ignore_case(s: &str) -> Result<Self, ParseError>
+        { Self::compile(s, true) }
+
+    fn compile(s: &str, ignore_case: bool) -> Result<Self, ParseError> {
+        let mut sets = [ByteSet::EMPTY; 4];
+        let mut chars = s.char_indices().peekable();
+        let mut n = 0;
+        while let Some((column, c)) = chars.next() {
+            if n == 4 { return Err(ParseError::new(column, ParseErrorKind::TooLong)) }
+            let set = &mut sets[n];
+            match c {
+                '?' => *set = ByteSet::ALL,
+                '[' => {
+                    let negate = chars.next_if(|&(_, c)| c == '!').is_some();
+                    let mut prev = None;
+                    loop {
+                        let (i, c) = chars.next().ok_or(ParseError::new(column, ParseErrorKind::Unterminated))?;
+                        match c {
+                            ']' if set.len() == 0 => return Err(ParseError::new(i, ParseErrorKind::InvalidChar)),
+                            ']' => break,
+                            '-' if prev.is_some() && chars.peek().is_some_and(|&(_, c)| c != ']') => {
+                                let (j, c) = chars.next().unwrap_or_default();
+                                let end = class_byte(c, j, &mut chars)?;
+                                let start = prev.take().unwrap_or_default();
+                                if end < start { return Err(ParseError::new(j, ParseErrorKind::InvalidChar)) }
+                                (start..=end).for_each(|b| insert(set, b, ignore_case));
+                            }
+                            c => {
+                                let b = class_byte(c, i, &mut chars)?;
+                                insert(set, b, ignore_case);
+                                prev = Some(b);
+                            }
+                        }
+                    }
+                    if negate { set.invert() }
+                }
+                ']' => return Err(ParseError::new(column, ParseErrorKind::InvalidChar)),
+                c => insert(set, class_byte(c, column, &mut chars)?, ignore_case),
+            }
+            n += 1;
+        }
+        match n {
+            0 => Err(ParseError::new(0, ParseErrorKind::Empty)),
+            1..=3 => Err(ParseError::new(s.len(), ParseErrorKind::TooShort)),
+            _ => Ok(Self::from_sets(sets)),
+        }
+    }
+
+    fn from_sets(sets: [ByteSet; 4]) -> Self {
+        let (mut mask, mut value, mut exact) = (0, 0, true);
+        for set in &sets {
+            let (m, v, e) = set.mask_value();
+            mask = mask << 8 | m as u32;
+            value = value << 8 | v as u32;
+            exact &= e;
+        }
+        Self { mask, value, classes: if exact { None } else { Some(sets) } }
+    }
+
+    /// Checks whether a code matches the pattern.
+    #[inline]
+    pub fn matches(&self, code: FourCC) -> bool {
+        code.to_u32_be() & self.mask == self.value
+            && self.classes.is_none_or(|sets| sets.iter().zip(code.0).all(|(set, b)| set.contains(b)))
+    }
+
+    /// Mask of the bits constrained by the pattern.
+    pub fn mask(&self) -> u32
+        { self.mask }
+
+    /// Value of the constrained bits.
+    pub fn value(&self) -> u32
+        { self.value }
+
+    /// Returns the mask and value if they alone describe the pattern.
+    pub fn mask_value(&self) -> Option<(u32, u32)>
+        { self.classes.is_none().then_some((self.mask, self.value)) }
+}
+
+impl FromStr for FourCCPattern {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+        { Self::parse(s) }
+}
+
+/// Pattern matching exactly one code.
+impl From<FourCC> for FourCCPattern {
+    fn from(code: FourCC) -> Self
+        { Self { mask: u32::MAX, value: code.to_u32_be(), classes: None } }
+}
+
+fn insert(set: &mut ByteSet, b: u8, ignore_case: bool) {
+    set.insert(b);
+    if ignore_case && b.is_ascii_alphabetic() { set.insert(b ^ 0x20) }
+}
+
+// Converts a literal or escaped character to its byte.
+fn class_byte(c: char, column: usize, chars: &mut impl Iterator<Item = (usize, char)>) -> Result<u8, ParseError> {
+    let c = match c {
+        '\\' => chars.next().ok_or(ParseError::new(column, ParseErrorKind::InvalidEscape))?.1,
+        c => c,
+    };
+    u8::try_from(c).map_err(|_| ParseError::new(column, ParseErrorKind::InvalidChar))
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(s: &str) -> FourCCPattern
+        { s.parse().unwrap() }
+
+    fn matches(pattern: &FourCCPattern, code: &str) -> bool
+        { pattern.matches(FourCC::from_str_const(code)) }
+
+    #[test]
+    fn wildcards() {
+        let avc = pattern("avc?");
+        assert!(matches(&avc, "avc1") && matches(&avc, "avc3"));
+        assert!(!matches(&avc, "AVC1") && !matches(&avc, "hvc1"));
+        assert_eq!(avc.mask_value(), Some((0xffffff00, 0x61766300)));
+        assert!(matches(&pattern("?VC1"), "WVC1"));
+        assert_eq!(pattern("????").mask_value(), Some((0, 0)));
+    }
+
+    #[test]
+    fn classes() {
+        let dolby = pattern("dvh[1e]");
+        assert!(matches(&dolby, "dvh1") && matches(&dolby, "dvhe"));
+        assert!(!matches(&dolby, "dvh2"));
+        assert!(dolby.mask_value().is_none());
+
+        let digits = pattern("mp4[0-9]");
+        assert!(matches(&digits, "mp49") && !matches(&digits, "mp4a"));
+        let other = pattern("mp4[!0-9]");
+        assert!(!matches(&other, "mp49") && matches(&other, "mp4a"));
+
+        // sets differing in a single bit are described by the mask
+        assert_eq!(pattern("avc[13]").mask_value(), Some((0xfffffffd, 0x61766331)));
+        assert!(matches(&pattern(r"\?[\]-]\[?"), "?][x"));
+        assert!(matches(&pattern("a[-b]?c"), "a-xc"));
+    }
+
+    #[test]
+    fn ignore_case() {
+        let xvid = FourCCPattern::parse_ignore_case("xvid").unwrap();
+        assert!(matches(&xvid, "XVID") && matches(&xvid, "xViD"));
+        assert!(!matches(&xvid, "DIVX"));
+        let digits = FourCCPattern::parse_ignore_case("DX[4-5]0").unwrap();
+        assert!(matches(&digits, "dx50"));
+        assert_eq!(digits.mask_value(), Some((0xdfdffeff, 0x44583430)));
+    }
+
+    #[test]
+    fn latin1() {
+        let meta = pattern("©???");
+        assert!(meta.matches(FourCC([0xa9, b'n', b'a', b'm'])));
+        assert!(!meta.matches(FourCC(*b"name")));
+        assert!(FourCCPattern::from(FourCC(*b"fmt ")).matches(FourCC(*b"fmt ")));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |s: &str| FourCCPattern::parse(s).map(|_| ()).map_err(|err| (err.column(), err.kind()));
+        assert_eq!(error(""), Err((0, ParseErrorKind::Empty)));
+        assert_eq!(error("avc"), Err((3, ParseErrorKind::TooShort)));
+        assert_eq!(error("avc1?"), Err((4, ParseErrorKind::TooLong)));
+        assert_eq!(error("av[c1"), Err((2, ParseErrorKind::Unterminated)));
+        assert_eq!(error("av[]1"), Err((3, ParseErrorKind::InvalidChar)));
+        assert_eq!(error("av]1"), Err((2, ParseErrorKind::InvalidChar)));
+        assert_eq!(error("av[z-a]"), Err((5, ParseErrorKind::InvalidChar)));
+        assert_eq!(error("avc€"), Err((3, ParseErrorKind::InvalidChar)));
+        assert_eq!(error("avc\\"), Err((3, ParseErrorKind::InvalidEscape)));
+    }
+}
@@ -0,0 +1,284 @@
+//! Compile-time perfect hash tables keyed by four character codes.
+//!
+//! The [`fourcc_map!`](crate::fourcc_map) macro builds a [`StaticMap`] whose
+//! perfect hash function is computed during constant evaluation. Lookups hash
+//! the code once and compare a single key, without allocation.
+//!
+//! # Examples
+//! ```
+//! use fourcc::{fourcc_map, FourCC};
+//! use fourcc::phf::StaticMap;
+//!
+//! fn fmt(data: &[u8]) -> usize { data.len() }
+//! fn data(_: &[u8]) -> usize { 0 }
+//!
+//! static HANDLERS: StaticMap<fn(&[u8]) -> usize, 2> = fourcc_map! {
+//!     b"fmt " => fmt,
+//!     b"data" => data,
+//! };
+//!
+//! let handler = HANDLERS.get(FourCC(*b"fmt ")).unwrap();
+//! assert_eq!(handler(b"abc"), 3);
+//! assert!(HANDLERS.get(FourCC(*b"LIST")).is_none());
+//! ```
+//!
+//! Duplicate keys fail compilation.
+//!
+//! ```compile_fail
+//! let map = fourcc::fourcc_map! { b"data" => 1, b"data" => 2 };
+//! ```
+
+use crate::hash::mix;
+use crate::FourCC;
+
+/// Average number of keys per displacement bucket.
+const LAMBDA: usize = 4;
+/// Number of seeds tried before giving up.
+const MAX_SEEDS: u64 = 64;
+
+/// Bucket and displacement inputs of a key.
+#[derive(Clone, Copy)]
+struct Hashes {
+    g: u32,
+    f1: u32,
+    f2: u32,
+}
+
+const fn hashes(code: FourCC, seed: u64) -> Hashes {
+    let h1 = mix(code.to_u32_be() as u64 ^ seed);
+    let h2 = mix(h1 ^ seed.rotate_left(32));
+    Hashes { g: (h1 >> 32) as u32, f1: h1 as u32, f2: h2 as u32 }
+}
+
+const fn displace(h: Hashes, d1: u32, d2: u32, len: usize) -> usize
+    { ((h.f1 as u64 * d1 as u64 + h.f2 as u64 + d2 as u64) % len as u64) as usize }
+
+//------------------------------------------------------------------------------
+
+/// Perfect hash index over a fixed set of codes.
+#[derive(Debug, Clone, Copy)]
+pub struct Index<const N: usize> {
+    seed: u64,
+    buckets: usize,
+    disps: [(u32, u32); N],
+    slots: [usize; N],
+    keys: [FourCC; N],
+}
+
+impl<const N: usize> Index<N> {
+    /// Builds the perfect hash index of a set of codes.
+    ///
+    /// # Panics
+    /// Panics if a code appears more than once, which fails compilation when
+    /// evaluated as a constant.
+    pub const fn new(keys: [FourCC; N]) -> Self {
+        let mut i = 0;
+        while i < N {
+            let mut j = i + 1;
+            while j < N {
+                if keys[i].to_u32_be() == keys[j].to_u32_be() { panic!("duplicate key in perfect hash table") }
+                j += 1;
+            }
+            i += 1;
+        }
+        let mut seed = 0;
+        while seed < MAX_SEEDS {
+            if let Some(index) = Self::try_seed(keys, seed) { return index }
+            seed += 1;
+        }
+        panic!("failed to build perfect hash table")
+    }
+
+    const fn try_seed(keys: [FourCC; N], seed: u64) -> Option<Self> {
+        let buckets = if N == 0 { 1 } else { N.div_ceil(LAMBDA) };
+        let mut index = Index { seed, buckets, disps: [(0, 0); N], slots: [usize::MAX; N], keys };
+        if N == 0 { return Some(index) }
+
+        // group key indices by bucket
+        let mut hashed = [Hashes { g: 0, f1: 0, f2: 0 }; N];
+        let mut counts = [0; N];
+        let mut i = 0;
+        while i < N {
+            hashed[i] = hashes(keys[i], seed);
+            counts[hashed[i].g as usize % buckets] += 1;
+            i += 1;
+        }
+        let mut starts = [0; N];
+        let mut b = 1;
+        while b < buckets {
+            starts[b] = starts[b - 1] + counts[b - 1];
+            b += 1;
+        }
+        let mut members = [0; N];
+        let mut filled = [0; N];
+        i = 0;
+        while i < N {
+            let b = hashed[i].g as usize % buckets;
+            members[starts[b] + filled[b]] = i;
+            filled[b] += 1;
+            i += 1;
+        }
+
+        // place the largest buckets first
+        let mut placed = [false; N];
+        let mut round = 0;
+        while round < buckets {
+            let mut bucket = 0;
+            b = 0;
+            while b < buckets {
+                if !placed[b] && (placed[bucket] || counts[b] > counts[bucket]) { bucket = b }
+                b += 1;
+            }
+            placed[bucket] = true;
+            match Self::place(&mut index.slots, &hashed, &members, starts[bucket], counts[bucket]) {
+                Some(disp) => index.disps[bucket] = disp,
+                None => return None,
+            }
+            round += 1;
+        }
+        Some(index)
+    }
+
+    // Finds a displacement mapping every key of a bucket to a free slot.
+    const fn place(slots: &mut [usize; N], hashed: &[Hashes; N], members: &[usize; N], start: usize, count: usize) -> Option<(u32, u32)> {
+        let mut d1 = 0;
+        while d1 < N as u32 {
+            let mut d2 = 0;
+            'displacement: while d2 < N as u32 {
+                let mut taken = [false; N];
+                let mut k = 0;
+                while k < count {
+                    let slot = displace(hashed[members[start + k]], d1, d2, N);
+                    if slots[slot] != usize::MAX || taken[slot] {
+                        d2 += 1;
+                        continue 'displacement
+                    }
+                    taken[slot] = true;
+                    k += 1;
+                }
+                k = 0;
+                while k < count {
+                    let key = members[start + k];
+                    slots[displace(hashed[key], d1, d2, N)] = key;
+                    k += 1;
+                }
+                return Some((d1, d2))
+            }
+            d1 += 1;
+        }
+        None
+    }
+
+    /// Returns the position of a code within the keys, if present.
+    #[inline]
+    pub const fn find(&self, code: FourCC) -> Option<usize> {
+        if N == 0 { return None }
+        let h = hashes(code, self.seed);
+        let (d1, d2) = self.disps[h.g as usize % self.buckets];
+        let key = self.slots[displace(h, d1, d2, N)];
+        if self.keys[key].to_u32_be() == code.to_u32_be() { Some(key) } else { None }
+    }
+
+    /// Codes of the index in their original order.
+    pub const fn keys(&self) -> &[FourCC; N]
+        { &self.keys }
+}
+
+//------------------------------------------------------------------------------
+
+/// Immutable map from four character codes to values with perfect hashing.
+///
+/// Maps are built by the [`fourcc_map!`](crate::fourcc_map) macro.
+#[derive(Debug, Clone, Copy)]
+pub struct StaticMap<V, const N: usize> {
+    index: Index<N>,
+    values: [V; N],
+}
+
+impl<V, const N: usize> StaticMap<V, N> {
+    /// Creates a map from an index and the values of its keys.
+    pub const fn new(index: Index<N>, values: [V; N]) -> Self
+        { Self { index, values } }
+
+    /// Returns the value of a code.
+    #[inline]
+    pub fn get(&self, code: FourCC) -> Option<&V>
+        { self.index.find(code).map(|i| &self.values[i]) }
+
+    /// Checks whether the map contains a code.
+    pub const fn contains_key(&self, code: FourCC) -> bool
+        { self.index.find(code).is_some() }
+
+    /// Number of entries in the map.
+    pub const fn len(&self) -> usize
+        { N }
+
+    /// Checks whether the map is empty.
+    pub const fn is_empty(&self) -> bool
+        { N == 0 }
+
+    /// Iterates over the entries in the order they were declared.
+    pub fn iter(&self) -> impl Iterator<Item = (FourCC, &V)>
+        { self.index.keys.iter().copied().zip(self.values.iter()) }
+}
+
+/// Creates a [`StaticMap`](crate::phf::StaticMap) from byte string keys.
+///
+/// The perfect hash function is computed at compile time, duplicate keys fail
+/// compilation. See the [`phf`](crate::phf) module for examples.
+#[macro_export]
+macro_rules! fourcc_map {
+    ($($key:expr => $value:expr),* $(,)?) => {
+        $crate::phf::StaticMap::new(
+            const { $crate::phf::Index::new([$($crate::FourCC::from_bytes(*$key)),*]) },
+            [$($value),*],
+        )
+    };
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let map = fourcc_map! {
+            b"RIFF" => 0, b"LIST" => 1, b"fmt " => 2, b"data" => 3, b"fact" => 4,
+            b"cue " => 5, b"INFO" => 6, b"INAM" => 7, b"avih" => 8, b"strh" => 9,
+        };
+        assert_eq!(map.len(), 10);
+        for (i, (code, &value)) in map.iter().enumerate() {
+            assert_eq!(value, i);
+            assert_eq!(map.get(code), Some(&value));
+        }
+        assert_eq!(map.get(FourCC(*b"JUNK")), None);
+        assert!(!map.contains_key(FourCC::from_u32_be(0)));
+    }
+
+    #[test]
+    fn many_keys() {
+        const KEYS: [FourCC; 200] = {
+            let mut keys = [FourCC([0; 4]); 200];
+            let mut i = 0;
+            while i < 200 {
+                keys[i] = FourCC::from_u32_be(0x61616161 + i as u32 * 0x0101);
+                i += 1;
+            }
+            keys
+        };
+        const INDEX: Index<200> = Index::new(KEYS);
+        for (i, &key) in KEYS.iter().enumerate() {
+            assert_eq!(INDEX.find(key), Some(i));
+        }
+        assert_eq!(INDEX.find(FourCC(*b"zzzz")), None);
+    }
+
+    #[test]
+    fn empty() {
+        let map: StaticMap<u8, 0> = fourcc_map! {};
+        assert!(map.is_empty());
+        assert_eq!(map.get(FourCC(*b"data")), None);
+    }
+}
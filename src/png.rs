@@ -0,0 +1,257 @@
+//! PNG chunk types and chunk stream reader.
+//!
+//! PNG chunk types are four ASCII letters where the case of each letter
+//! encodes a property bit: uppercase means the bit is clear.
+//!
+//! | Byte | Lowercase     | Uppercase   |
+//! |------|---------------|-------------|
+//! | 0    | ancillary     | critical    |
+//! | 1    | private       | public      |
+//! | 2    | reserved      | valid       |
+//! | 3    | safe to copy  | unsafe      |
+//!
+//! With the `std` feature, PNG, APNG, MNG and JNG streams are read with a
+//! `ChunkReader`, which verifies the CRC-32 of every chunk and the sequence
+//! numbers of APNG frames.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use fourcc::png::{self, ChunkReader, IEND};
+//!
+//! let mut data = png::PNG_SIGNATURE.to_vec();
+//! data.extend_from_slice(b"\0\0\0\0IEND\xae\x42\x60\x82");
+//! let mut reader = ChunkReader::new(&data[..]).unwrap();
+//! let chunk = reader.next_chunk().unwrap().unwrap();
+//! assert_eq!(chunk.id, IEND);
+//! assert!(chunk.crc_valid);
+//! assert!(reader.next_chunk().unwrap().is_none());
+//! # }
+//! ```
+
+use core::fmt;
+
+use crate::{FourCC, ValidationError, ValidationProfile};
+
+#[cfg(feature = "std")]
+mod reader;
+
+#[cfg(feature = "std")]
+pub use reader::{
+    read_animation, Animation, AnimationControl, BlendOp, Chunk, ChunkReader, DisposeOp,
+    FrameControl, Signature,
+};
+
+/// Signature of a PNG or APNG stream.
+pub const PNG_SIGNATURE: [u8; 8] = *b"\x89PNG\r\n\x1a\n";
+/// Signature of an MNG stream.
+pub const MNG_SIGNATURE: [u8; 8] = *b"\x8aMNG\r\n\x1a\n";
+/// Signature of a JNG stream.
+pub const JNG_SIGNATURE: [u8; 8] = *b"\x8bJNG\r\n\x1a\n";
+
+/// PNG image header.
+pub const IHDR: FourCC = FourCC(*b"IHDR");
+/// PNG palette.
+pub const PLTE: FourCC = FourCC(*b"PLTE");
+/// PNG image data.
+pub const IDAT: FourCC = FourCC(*b"IDAT");
+/// End of a PNG or JNG stream.
+pub const IEND: FourCC = FourCC(*b"IEND");
+/// APNG animation control.
+pub const ACTL: FourCC = FourCC(*b"acTL");
+/// APNG frame control.
+pub const FCTL: FourCC = FourCC(*b"fcTL");
+/// APNG frame data.
+pub const FDAT: FourCC = FourCC(*b"fdAT");
+/// MNG header.
+pub const MHDR: FourCC = FourCC(*b"MHDR");
+/// End of an MNG stream.
+pub const MEND: FourCC = FourCC(*b"MEND");
+
+//------------------------------------------------------------------------------
+
+/// Lookup table of the reflected CRC-32 polynomial used by PNG.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 { 0xedb88320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+/// Continues a CRC-32 computation with more bytes, starting from `0`.
+pub const fn crc32_update(crc: u32, bytes: &[u8]) -> u32 {
+    let mut crc = !crc;
+    let mut i = 0;
+    while i < bytes.len() {
+        crc = CRC_TABLE[((crc ^ bytes[i] as u32) & 0xff) as usize] ^ (crc >> 8);
+        i += 1;
+    }
+    !crc
+}
+
+/// Computes the CRC-32 of a chunk's type and data.
+pub const fn chunk_crc(id: FourCC, data: &[u8]) -> u32
+    { crc32_update(crc32_update(0, &id.0), data) }
+
+//------------------------------------------------------------------------------
+
+/// Bit distinguishing lowercase from uppercase ASCII letters.
+const CASE_BIT: u8 = 0x20;
+
+/// PNG chunk type, a `FourCC` of four ASCII letters.
+///
+/// # Examples
+/// ```
+/// use fourcc::FourCC;
+/// use fourcc::png::PngChunkType;
+///
+/// let text = PngChunkType::new(FourCC(*b"tEXt")).unwrap();
+/// assert!(!text.is_critical());
+/// assert!(text.is_public());
+/// assert!(text.is_safe_to_copy());
+///
+/// let private = PngChunkType::new(FourCC(*b"prIv")).unwrap();
+/// assert_eq!(private.with_critical(true).with_public(true), FourCC(*b"PRIv"));
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct PngChunkType(FourCC);
+
+impl PngChunkType {
+    /// Creates a new chunk type, requiring every byte to be an ASCII letter.
+    pub fn new(code: FourCC) -> Result<Self, ValidationError> {
+        code.validate(ValidationProfile::Png)?;
+        Ok(Self(code))
+    }
+
+    /// Creates a new chunk type without validating it.
+    pub const fn new_unchecked(code: FourCC) -> Self
+        { Self(code) }
+
+    /// Underlying four character code.
+    pub const fn code(self) -> FourCC
+        { self.0 }
+
+    const fn bit(self, i: usize) -> bool
+        { self.0.0[i] & CASE_BIT == 0 }
+
+    const fn with_bit(mut self, i: usize, set: bool) -> Self {
+        if set { self.0.0[i] &= !CASE_BIT } else { self.0.0[i] |= CASE_BIT }
+        self
+    }
+
+    /// Checks whether decoders must understand the chunk to display the image.
+    pub const fn is_critical(self) -> bool
+        { self.bit(0) }
+
+    /// Checks whether the chunk is part of the PNG specification or
+    /// registered.
+    pub const fn is_public(self) -> bool
+        { self.bit(1) }
+
+    /// Checks whether the reserved bit is clear, as required by the current
+    /// PNG specification.
+    pub const fn is_reserved_valid(self) -> bool
+        { self.bit(2) }
+
+    /// Checks whether editors may copy the chunk after modifying critical
+    /// chunks without understanding it.
+    pub const fn is_safe_to_copy(self) -> bool
+        { !self.bit(3) }
+
+    /// Returns the chunk type with the critical bit set or cleared.
+    pub const fn with_critical(self, critical: bool) -> Self
+        { self.with_bit(0, critical) }
+
+    /// Returns the chunk type with the public bit set or cleared.
+    pub const fn with_public(self, public: bool) -> Self
+        { self.with_bit(1, public) }
+
+    /// Returns the chunk type with the reserved bit set or cleared.
+    pub const fn with_reserved_valid(self, valid: bool) -> Self
+        { self.with_bit(2, valid) }
+
+    /// Returns the chunk type with the safe-to-copy bit set or cleared.
+    pub const fn with_safe_to_copy(self, safe: bool) -> Self
+        { self.with_bit(3, !safe) }
+}
+
+impl TryFrom<FourCC> for PngChunkType {
+    type Error = ValidationError;
+
+    fn try_from(code: FourCC) -> Result<Self, Self::Error>
+        { Self::new(code) }
+}
+
+impl From<PngChunkType> for FourCC {
+    fn from(chunk_type: PngChunkType) -> FourCC
+        { chunk_type.0 }
+}
+
+impl PartialEq<FourCC> for PngChunkType {
+    fn eq(&self, other: &FourCC) -> bool
+        { self.0 == *other }
+}
+
+impl fmt::Display for PngChunkType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        { fmt::Display::fmt(&self.0, f) }
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValidationReason;
+
+    fn chunk(code: &str) -> PngChunkType
+        { PngChunkType::new(FourCC::from_str_const(code)).unwrap() }
+
+    #[test]
+    fn properties() {
+        let ihdr = chunk("IHDR");
+        assert!(ihdr.is_critical() && ihdr.is_public() && ihdr.is_reserved_valid());
+        assert!(!ihdr.is_safe_to_copy());
+
+        let phys = chunk("pHYs");
+        assert!(!phys.is_critical() && phys.is_public() && phys.is_safe_to_copy());
+
+        let private = chunk("abcd");
+        assert!(!private.is_public() && !private.is_reserved_valid());
+    }
+
+    #[test]
+    fn set_bits() {
+        let code = chunk("IDAT");
+        assert_eq!(code.with_critical(false), FourCC(*b"iDAT"));
+        assert_eq!(code.with_public(false), FourCC(*b"IdAT"));
+        assert_eq!(code.with_reserved_valid(false), FourCC(*b"IDaT"));
+        assert_eq!(code.with_safe_to_copy(true), FourCC(*b"IDAt"));
+        assert_eq!(code.with_critical(false).with_critical(true), code);
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc32_update(0, b"123456789"), 0xcbf43926);
+        assert_eq!(crc32_update(crc32_update(0, b"1234"), b"56789"), 0xcbf43926);
+        assert_eq!(chunk_crc(IEND, &[]), 0xae426082);
+    }
+
+    #[test]
+    fn validation() {
+        let err = PngChunkType::new(FourCC(*b"IDA1")).unwrap_err();
+        assert_eq!((err.index, err.reason), (3, ValidationReason::NotAlphabetic));
+        assert!(PngChunkType::try_from(FourCC(*b"fmt ")).is_err());
+        assert_eq!(chunk("tIME").to_string(), "tIME");
+    }
+}
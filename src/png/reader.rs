@@ -0,0 +1,421 @@
+//! PNG, APNG, MNG and JNG chunk stream reader.
+
+use std::io::{self, Read};
+
+use super::{chunk_crc, PngChunkType, ACTL, FCTL, FDAT, IEND, JNG_SIGNATURE, MEND, MNG_SIGNATURE, PNG_SIGNATURE};
+use crate::iff::invalid;
+use crate::FourCC;
+
+/// Largest chunk length allowed by the PNG specification.
+const MAX_LENGTH: u32 = 0x7fff_ffff;
+/// Length of an `acTL` chunk.
+const ACTL_LENGTH: usize = 8;
+/// Length of an `fcTL` chunk.
+const FCTL_LENGTH: usize = 26;
+
+/// Kind of stream, identified by its signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signature {
+    /// PNG or APNG image, ending with `IEND`.
+    Png,
+    /// MNG animation, ending with `MEND`.
+    Mng,
+    /// JNG image, ending with `IEND`.
+    Jng,
+}
+
+impl Signature {
+    /// Eight byte signature at the start of the stream.
+    pub const fn bytes(self) -> [u8; 8] {
+        match self {
+            Self::Png => PNG_SIGNATURE,
+            Self::Mng => MNG_SIGNATURE,
+            Self::Jng => JNG_SIGNATURE,
+        }
+    }
+
+    /// Chunk type ending the stream.
+    pub const fn end(self) -> FourCC {
+        match self {
+            Self::Png | Self::Jng => IEND,
+            Self::Mng => MEND,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Chunk read from a PNG stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Chunk type.
+    pub id: FourCC,
+    /// Byte offset of the chunk length within the stream, including the
+    /// signature.
+    pub offset: u64,
+    /// Chunk data.
+    pub data: Vec<u8>,
+    /// CRC-32 as stored in the stream.
+    pub crc: u32,
+    /// Whether the stored CRC-32 matches the chunk type and data.
+    pub crc_valid: bool,
+}
+
+impl Chunk {
+    /// Length of the chunk data.
+    pub fn length(&self) -> u32
+        { self.data.len() as u32 }
+
+    /// Chunk type with its property bits.
+    pub fn chunk_type(&self) -> PngChunkType
+        { PngChunkType::new_unchecked(self.id) }
+
+    /// Sequence number of an APNG `fcTL` or `fdAT` chunk.
+    pub fn sequence_number(&self) -> Option<u32> {
+        if self.id != FCTL && self.id != FDAT { return None }
+        Some(read_u32(self.data.get(..4)?))
+    }
+
+    /// Parses an APNG `acTL` chunk.
+    pub fn animation_control(&self) -> Option<AnimationControl> {
+        if self.id != ACTL || self.data.len() != ACTL_LENGTH { return None }
+        Some(AnimationControl { num_frames: read_u32(&self.data[0..4]), num_plays: read_u32(&self.data[4..8]) })
+    }
+
+    /// Parses an APNG `fcTL` chunk.
+    pub fn frame_control(&self) -> Option<FrameControl> {
+        if self.id != FCTL || self.data.len() != FCTL_LENGTH { return None }
+        let d = &self.data;
+        Some(FrameControl {
+            sequence_number: read_u32(&d[0..4]),
+            width: read_u32(&d[4..8]),
+            height: read_u32(&d[8..12]),
+            x_offset: read_u32(&d[12..16]),
+            y_offset: read_u32(&d[16..20]),
+            delay_num: u16::from_be_bytes([d[20], d[21]]),
+            delay_den: u16::from_be_bytes([d[22], d[23]]),
+            dispose_op: DisposeOp::from_u8(d[24])?,
+            blend_op: BlendOp::from_u8(d[25])?,
+        })
+    }
+}
+
+/// APNG animation control from an `acTL` chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnimationControl {
+    /// Number of frames.
+    pub num_frames: u32,
+    /// Number of times to loop, `0` loops forever.
+    pub num_plays: u32,
+}
+
+/// APNG frame region disposal after rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DisposeOp {
+    /// Leave the frame region as is.
+    None,
+    /// Clear the frame region to transparent black.
+    Background,
+    /// Revert the frame region to its previous contents.
+    Previous,
+}
+
+impl DisposeOp {
+    fn from_u8(op: u8) -> Option<Self> {
+        match op {
+            0 => Some(Self::None),
+            1 => Some(Self::Background),
+            2 => Some(Self::Previous),
+            _ => None,
+        }
+    }
+}
+
+/// APNG frame blending with the output buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlendOp {
+    /// Overwrite the frame region.
+    Source,
+    /// Alpha composite the frame over the frame region.
+    Over,
+}
+
+impl BlendOp {
+    fn from_u8(op: u8) -> Option<Self> {
+        match op {
+            0 => Some(Self::Source),
+            1 => Some(Self::Over),
+            _ => None,
+        }
+    }
+}
+
+/// APNG frame control from an `fcTL` chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameControl {
+    /// Position in the sequence of `fcTL` and `fdAT` chunks.
+    pub sequence_number: u32,
+    /// Frame width in pixels.
+    pub width: u32,
+    /// Frame height in pixels.
+    pub height: u32,
+    /// Horizontal position of the frame.
+    pub x_offset: u32,
+    /// Vertical position of the frame.
+    pub y_offset: u32,
+    /// Numerator of the frame delay in seconds.
+    pub delay_num: u16,
+    /// Denominator of the frame delay in seconds, `0` means 1/100.
+    pub delay_den: u16,
+    /// Disposal of the frame region after rendering.
+    pub dispose_op: DisposeOp,
+    /// Blending of the frame region before rendering.
+    pub blend_op: BlendOp,
+}
+
+/// Animation control and frames of an APNG stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Animation {
+    /// Animation control.
+    pub control: AnimationControl,
+    /// Frame controls in sequence order.
+    pub frames: Vec<FrameControl>,
+}
+
+//------------------------------------------------------------------------------
+
+/// Reader yielding the chunks of a PNG, APNG, MNG or JNG stream.
+///
+/// Chunks are yielded until the end chunk of the stream, `IEND` or `MEND`.
+/// A CRC-32 mismatch is an error unless [`ChunkReader::lenient_crc`] is
+/// enabled, in which case the chunk is yielded with `crc_valid` unset. The
+/// sequence numbers of APNG `fcTL` and `fdAT` chunks must be consecutive.
+#[derive(Debug)]
+pub struct ChunkReader<R> {
+    reader: R,
+    signature: Signature,
+    offset: u64,
+    done: bool,
+    lenient_crc: bool,
+    sequence: u32,
+}
+
+impl<R: Read> ChunkReader<R> {
+    /// Creates a reader, consuming the stream signature.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut bytes = [0; 8];
+        reader.read_exact(&mut bytes)?;
+        let signature = [Signature::Png, Signature::Mng, Signature::Jng].into_iter()
+            .find(|signature| signature.bytes() == bytes)
+            .ok_or_else(|| invalid("not a PNG, MNG or JNG stream"))?;
+        Ok(Self { reader, signature, offset: 8, done: false, lenient_crc: false, sequence: 0 })
+    }
+
+    /// Sets whether chunks with a CRC-32 mismatch are yielded instead of
+    /// failing.
+    pub fn lenient_crc(mut self, lenient: bool) -> Self {
+        self.lenient_crc = lenient;
+        self
+    }
+
+    /// Kind of stream.
+    pub fn signature(&self) -> Signature
+        { self.signature }
+
+    /// Reads the next chunk.
+    ///
+    /// Returns `None` after the end chunk of the stream.
+    pub fn next_chunk(&mut self) -> io::Result<Option<Chunk>> {
+        if self.done { return Ok(None) }
+        let result = self.read_chunk();
+        match &result {
+            Ok(chunk) => self.done = chunk.id == self.signature.end(),
+            Err(_) => self.done = true,
+        }
+        result.map(Some)
+    }
+
+    fn read_chunk(&mut self) -> io::Result<Chunk> {
+        let mut header = [0; 8];
+        self.reader.read_exact(&mut header)?;
+        let length = read_u32(&header[..4]);
+        let id = FourCC([header[4], header[5], header[6], header[7]]);
+        if length > MAX_LENGTH { return Err(invalid("chunk length exceeds 2^31 - 1")) }
+        if PngChunkType::new(id).is_err() { return Err(invalid("chunk type is not four ASCII letters")) }
+
+        let mut data = Vec::new();
+        (&mut self.reader).take(length as u64).read_to_end(&mut data)?;
+        if data.len() != length as usize { return Err(io::ErrorKind::UnexpectedEof.into()) }
+        let mut crc = [0; 4];
+        self.reader.read_exact(&mut crc)?;
+        let crc = read_u32(&crc);
+        let crc_valid = crc == chunk_crc(id, &data);
+        if !crc_valid && !self.lenient_crc { return Err(invalid("chunk CRC-32 mismatch")) }
+
+        let chunk = Chunk { id, offset: self.offset, data, crc, crc_valid };
+        self.offset += 12 + length as u64;
+        if id == FCTL || id == FDAT {
+            // corrupt chunks only advance the sequence
+            if crc_valid { self.check_sequence(&chunk)? }
+            self.sequence = self.sequence.wrapping_add(1);
+        }
+        Ok(chunk)
+    }
+
+    fn check_sequence(&self, chunk: &Chunk) -> io::Result<()> {
+        if chunk.id == FCTL && chunk.frame_control().is_none() { return Err(invalid("malformed fcTL chunk")) }
+        match chunk.sequence_number() {
+            Some(sequence) if sequence == self.sequence => Ok(()),
+            Some(_) => Err(invalid("APNG sequence number out of order")),
+            None => Err(invalid("malformed fdAT chunk")),
+        }
+    }
+
+    /// Unwraps the underlying reader.
+    pub fn into_inner(self) -> R
+        { self.reader }
+}
+
+impl<R: Read> Iterator for ChunkReader<R> {
+    type Item = io::Result<Chunk>;
+
+    fn next(&mut self) -> Option<Self::Item>
+        { self.next_chunk().transpose() }
+}
+
+/// Reads the animation control and frame controls of an APNG stream.
+///
+/// Returns `None` for a stream without an `acTL` chunk.
+pub fn read_animation<R: Read>(reader: R) -> io::Result<Option<Animation>> {
+    let mut control = None;
+    let mut frames = Vec::new();
+    for chunk in ChunkReader::new(reader)? {
+        let chunk = chunk?;
+        if chunk.id == ACTL {
+            control = Some(chunk.animation_control().ok_or_else(|| invalid("malformed acTL chunk"))?);
+        } else if let Some(frame) = chunk.frame_control() {
+            frames.push(frame);
+        }
+    }
+    let Some(control) = control else { return Ok(None) };
+    if frames.len() as u64 != control.num_frames as u64 {
+        return Err(invalid("number of fcTL chunks does not match acTL"))
+    }
+    Ok(Some(Animation { control, frames }))
+}
+
+fn read_u32(bytes: &[u8]) -> u32
+    { u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) }
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::png::{IDAT, IHDR, MHDR};
+
+    fn chunk(data: &mut Vec<u8>, id: FourCC, payload: &[u8]) {
+        data.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        data.extend_from_slice(&id.0);
+        data.extend_from_slice(payload);
+        data.extend_from_slice(&chunk_crc(id, payload).to_be_bytes());
+    }
+
+    fn fctl(sequence: u32) -> Vec<u8> {
+        let mut data = sequence.to_be_bytes().to_vec();
+        data.extend_from_slice(&[0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 10, 1, 0]);
+        data
+    }
+
+    fn apng() -> Vec<u8> {
+        let mut data = PNG_SIGNATURE.to_vec();
+        chunk(&mut data, IHDR, &[0, 0, 0, 2, 0, 0, 0, 1, 8, 6, 0, 0, 0]);
+        chunk(&mut data, ACTL, &[0, 0, 0, 2, 0, 0, 0, 0]);
+        chunk(&mut data, FCTL, &fctl(0));
+        chunk(&mut data, IDAT, b"frame0");
+        chunk(&mut data, FCTL, &fctl(1));
+        chunk(&mut data, FDAT, b"\0\0\0\x02frame1");
+        chunk(&mut data, IEND, &[]);
+        data
+    }
+
+    #[test]
+    fn read_chunks() {
+        let chunks: Vec<Chunk> = ChunkReader::new(&apng()[..]).unwrap().collect::<io::Result<_>>().unwrap();
+        let ids: Vec<FourCC> = chunks.iter().map(|chunk| chunk.id).collect();
+        assert_eq!(ids, [IHDR, ACTL, FCTL, IDAT, FCTL, FDAT, IEND]);
+        assert_eq!(chunks[0].offset, 8);
+        assert_eq!(chunks[1].offset, 33);
+        assert_eq!(chunks[3].data, b"frame0");
+        assert_eq!(chunks[5].sequence_number(), Some(2));
+        assert!(chunks.iter().all(|chunk| chunk.crc_valid));
+        assert!(chunks[0].chunk_type().is_critical());
+    }
+
+    #[test]
+    fn animation() {
+        let animation = read_animation(&apng()[..]).unwrap().unwrap();
+        assert_eq!(animation.control, AnimationControl { num_frames: 2, num_plays: 0 });
+        assert_eq!(animation.frames.len(), 2);
+        let frame = animation.frames[1];
+        assert_eq!((frame.sequence_number, frame.width, frame.height), (1, 2, 1));
+        assert_eq!((frame.delay_num, frame.delay_den), (1, 10));
+        assert_eq!((frame.dispose_op, frame.blend_op), (DisposeOp::Background, BlendOp::Source));
+
+        let mut data = PNG_SIGNATURE.to_vec();
+        chunk(&mut data, IEND, &[]);
+        assert_eq!(read_animation(&data[..]).unwrap(), None);
+    }
+
+    #[test]
+    fn sequence_order() {
+        let mut data = PNG_SIGNATURE.to_vec();
+        chunk(&mut data, FCTL, &fctl(0));
+        chunk(&mut data, FDAT, b"\0\0\0\x02data");
+        let mut reader = ChunkReader::new(&data[..]).unwrap();
+        assert!(reader.next_chunk().unwrap().is_some());
+        let err = reader.next_chunk().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(reader.next_chunk().unwrap().is_none());
+    }
+
+    #[test]
+    fn crc_mismatch() {
+        let mut data = apng();
+        data[8 + 8] ^= 1;
+        let err = ChunkReader::new(&data[..]).unwrap().next_chunk().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let chunks: Vec<Chunk> = ChunkReader::new(&data[..]).unwrap().lenient_crc(true)
+            .collect::<io::Result<_>>().unwrap();
+        assert_eq!(chunks.len(), 7);
+        assert!(!chunks[0].crc_valid);
+        assert!(chunks[1..].iter().all(|chunk| chunk.crc_valid));
+    }
+
+    #[test]
+    fn mng() {
+        let mut data = MNG_SIGNATURE.to_vec();
+        chunk(&mut data, MHDR, &[0; 28]);
+        chunk(&mut data, MEND, &[]);
+        chunk(&mut data, IEND, &[]);
+        let mut reader = ChunkReader::new(&data[..]).unwrap();
+        assert_eq!(reader.signature(), Signature::Mng);
+        assert_eq!(reader.by_ref().count(), 2);
+    }
+
+    #[test]
+    fn invalid_streams() {
+        let err = ChunkReader::new(&b"GIF89a\0\0"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut data = PNG_SIGNATURE.to_vec();
+        data.extend_from_slice(b"\0\0\0\x10IDATshort");
+        let err = ChunkReader::new(&data[..]).unwrap().next_chunk().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut data = PNG_SIGNATURE.to_vec();
+        chunk(&mut data, FourCC(*b"ID4T"), &[]);
+        assert!(ChunkReader::new(&data[..]).unwrap().next_chunk().is_err());
+    }
+}
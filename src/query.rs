@@ -0,0 +1,313 @@
+//! Path queries over chunk and box trees.
+//!
+//! A query is a `/` separated list of segments, each selecting among the
+//! children of the nodes matched so far, starting from the top level nodes:
+//!
+//! * `moov` matches nodes with the given identifier, codes shorter than four
+//!   characters are padded with spaces and characters up to `U+00FF` map to
+//!   a single byte, so `©nam` matches `[0xa9, b'n', b'a', b'm']`
+//! * `*` matches any node
+//! * `LIST:INFO` additionally requires the form type of a group chunk
+//! * `trak[1]` selects a match by its zero-based index among its siblings
+//! * `**` matches any number of levels, including none
+//!
+//! # Examples
+//! ```
+//! use std::io::Cursor;
+//! use fourcc::{query::Query, riff};
+//!
+//! let data = b"RIFF\x1c\0\0\0WAVELIST\x10\0\0\0INFOINAM\x03\0\0\0abc\0";
+//! let tree = riff::read_tree(Cursor::new(&data[..])).unwrap();
+//!
+//! let query: Query = "RIFF:WAVE/LIST:INFO/INAM".parse().unwrap();
+//! let inam = query.select(&tree);
+//! assert_eq!(inam[0].chunk.offset, 24);
+//! assert_eq!("**/INAM".parse::<Query>().unwrap().select(&tree), inam);
+//! assert!("RIFF:AVI/*".parse::<Query>().unwrap().select(&tree).is_empty());
+//! ```
+
+use core::fmt;
+use core::str::FromStr;
+use std::collections::HashSet;
+
+use crate::parse::{ParseError, ParseErrorKind};
+use crate::{iff, isobmff, FourCC};
+
+/// Node of a tree which can be queried.
+pub trait TreeNode: Sized {
+    /// Identifier of the node.
+    fn id(&self) -> FourCC;
+
+    /// Form type of a group node.
+    fn form_type(&self) -> Option<FourCC>
+        { None }
+
+    /// Byte offset of the node within its stream.
+    fn offset(&self) -> u64;
+
+    /// Nested nodes.
+    fn children(&self) -> &[Self];
+}
+
+impl TreeNode for iff::Node {
+    fn id(&self) -> FourCC
+        { self.chunk.id }
+
+    fn form_type(&self) -> Option<FourCC>
+        { self.chunk.form_type }
+
+    fn offset(&self) -> u64
+        { self.chunk.offset }
+
+    fn children(&self) -> &[Self]
+        { &self.children }
+}
+
+impl TreeNode for isobmff::Node {
+    fn id(&self) -> FourCC
+        { self.header.box_type }
+
+    fn offset(&self) -> u64
+        { self.header.offset }
+
+    fn children(&self) -> &[Self]
+        { &self.children }
+}
+
+//------------------------------------------------------------------------------
+
+/// Segment of a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment {
+    /// Children matching an identifier and form type, `None` matches any.
+    Step { id: Option<FourCC>, form_type: Option<FourCC>, index: Option<usize> },
+    /// Nodes at any depth, including the current nodes.
+    Descendants,
+}
+
+impl Segment {
+    fn matches<N: TreeNode>(&self, node: &N) -> bool {
+        match *self {
+            Segment::Step { id, form_type, .. } =>
+                id.is_none_or(|id| id == node.id()) && form_type.is_none_or(|t| Some(t) == node.form_type()),
+            Segment::Descendants => true,
+        }
+    }
+}
+
+/// Compiled path query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    segments: Vec<Segment>,
+}
+
+impl Query {
+    /// Parses a query, see the [module documentation](self) for its syntax.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        if s.is_empty() { return Err(ParseError::new(0, ParseErrorKind::Empty)) }
+        let mut segments = Vec::new();
+        let mut column = 0;
+        for segment in s.split('/') {
+            segments.push(parse_segment(segment, column)?);
+            column += segment.len() + 1;
+        }
+        Ok(Self { segments })
+    }
+
+    /// Returns the nodes matching the query in document order, that is by
+    /// increasing offset.
+    pub fn select<'a, N: TreeNode>(&self, nodes: &'a [N]) -> Vec<&'a N> {
+        // `None` stands for the root above the top level nodes
+        let mut current: Vec<Option<&'a N>> = vec![None];
+        for segment in &self.segments {
+            let mut next = Vec::new();
+            for &context in &current {
+                let children = context.map_or(nodes, N::children);
+                match *segment {
+                    Segment::Descendants => {
+                        next.push(context);
+                        descendants(children, &mut next);
+                    }
+                    Segment::Step { index, .. } => {
+                        let mut matches = children.iter().filter(|node| segment.matches(*node));
+                        match index {
+                            Some(i) => next.extend(matches.nth(i).map(Some)),
+                            None => next.extend(matches.map(Some)),
+                        }
+                    }
+                }
+            }
+            dedup(&mut next);
+            current = next;
+        }
+        let mut selected: Vec<&'a N> = current.into_iter().flatten().collect();
+        selected.sort_by_key(|node| node.offset());
+        selected
+    }
+
+    /// Returns the first node matching the query.
+    pub fn first<'a, N: TreeNode>(&self, nodes: &'a [N]) -> Option<&'a N>
+        { self.select(nodes).into_iter().next() }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+        { Self::parse(s) }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 { f.write_str("/")? }
+            match *segment {
+                Segment::Descendants => f.write_str("**")?,
+                Segment::Step { id, form_type, index } => {
+                    match id {
+                        Some(id) => write_code(id, f)?,
+                        None => f.write_str("*")?,
+                    }
+                    if let Some(form_type) = form_type {
+                        f.write_str(":")?;
+                        write_code(form_type, f)?;
+                    }
+                    if let Some(index) = index { write!(f, "[{index}]")? }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_code(code: FourCC, f: &mut fmt::Formatter) -> fmt::Result {
+    let len = code.0.iter().rposition(|&b| b != b' ').map_or(0, |i| i + 1);
+    code.0[..len].iter().try_for_each(|&b| fmt::Write::write_char(f, b as char))
+}
+
+// Walks the nodes depth first with an explicit stack, as trees built by hand
+// may be nested arbitrarily deep.
+fn descendants<'a, N: TreeNode>(nodes: &'a [N], out: &mut Vec<Option<&'a N>>) {
+    let mut stack = vec![nodes.iter()];
+    while let Some(siblings) = stack.last_mut() {
+        match siblings.next() {
+            Some(node) => {
+                out.push(Some(node));
+                stack.push(node.children().iter());
+            }
+            None => { stack.pop(); }
+        }
+    }
+}
+
+// Removes repeated nodes reached through more than one context.
+fn dedup<N>(nodes: &mut Vec<Option<&N>>) {
+    let mut seen = HashSet::new();
+    nodes.retain(|node| seen.insert(node.map_or(core::ptr::null(), |node| node as *const N)));
+}
+
+//------------------------------------------------------------------------------
+
+fn parse_segment(s: &str, column: usize) -> Result<Segment, ParseError> {
+    if s == "**" { return Ok(Segment::Descendants) }
+    let (s, index) = match s.find('[') {
+        Some(open) => {
+            let digits = s[open + 1..].strip_suffix(']')
+                .ok_or_else(|| ParseError::new(column + open, ParseErrorKind::Unterminated))?;
+            let index = digits.parse()
+                .map_err(|_| ParseError::new(column + open + 1, ParseErrorKind::InvalidNumber))?;
+            (&s[..open], Some(index))
+        }
+        None => (s, None),
+    };
+    let (id, form_type) = match s.split_once(':') {
+        Some((id, form_type)) => (id, Some(parse_code(form_type, column + id.len() + 1)?)),
+        None => (s, None),
+    };
+    let id = if id == "*" { None } else { Some(parse_code(id, column)?) };
+    Ok(Segment::Step { id, form_type, index })
+}
+
+fn parse_code(s: &str, column: usize) -> Result<FourCC, ParseError> {
+    if s.is_empty() { return Err(ParseError::new(column, ParseErrorKind::Empty)) }
+    let mut code = [b' '; 4];
+    for (n, (i, c)) in s.char_indices().enumerate() {
+        if n == 4 { return Err(ParseError::new(column + i, ParseErrorKind::TooLong)) }
+        if matches!(c, '*' | '[' | ']' | ':') { return Err(ParseError::new(column + i, ParseErrorKind::InvalidChar)) }
+        code[n] = u8::try_from(c).map_err(|_| ParseError::new(column + i, ParseErrorKind::InvalidChar))?;
+    }
+    Ok(FourCC(code))
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::isobmff::tests::boxed;
+    use std::io::Cursor;
+
+    fn mp4() -> Vec<isobmff::Node> {
+        let trak = |handler: &[u8; 4]| boxed(b"trak", &boxed(b"mdia", &boxed(b"hdlr", &[&[0; 12][..], handler].concat())));
+        let udta = boxed(b"udta", &boxed(&[0xa9, b'n', b'a', b'm'], b"title"));
+        let moov = boxed(b"moov", &[trak(b"vide"), trak(b"soun"), udta].concat());
+        isobmff::read_tree(Cursor::new([boxed(b"ftyp", b"isom"), moov].concat())).unwrap()
+    }
+
+    fn offsets<N: TreeNode>(query: &str, nodes: &[N]) -> Vec<u64>
+        { query.parse::<Query>().unwrap().select(nodes).iter().map(|node| node.offset()).collect() }
+
+    #[test]
+    fn select_boxes() {
+        let tree = mp4();
+        assert_eq!(offsets("moov/trak", &tree), [20, 60]);
+        assert_eq!(offsets("moov/trak[1]/mdia/hdlr", &tree), [76]);
+        assert!(offsets("moov/trak[2]", &tree).is_empty());
+        assert_eq!(offsets("moov/*/mdia", &tree), [28, 68]);
+        assert_eq!(offsets("**/hdlr", &tree), [36, 76]);
+        assert_eq!(offsets("**/©nam", &tree), [108]);
+        assert_eq!(offsets("moov/**", &tree).len(), 9);
+        assert_eq!(offsets("**/**/hdlr", &tree), [36, 76]);
+        assert_eq!(offsets("*", &tree), [0, 12]);
+    }
+
+    #[test]
+    fn select_chunks() {
+        let mut data = b"FORM\0\0\0\x26ILBMLIST\0\0\0\x0eINFONAME\0\0\0\x02hi".to_vec();
+        data.extend_from_slice(b"LIST\0\0\0\x04PROP");
+        let tree = iff::ChunkReader::new(Cursor::new(data)).unwrap().read_tree().unwrap();
+        assert_eq!(offsets("FORM:ILBM/LIST", &tree), [12, 34]);
+        assert_eq!(offsets("FORM/LIST:INFO/NAME", &tree), [24]);
+        assert_eq!(offsets("*/*:PROP", &tree), [34]);
+        assert!(offsets("FORM:8SVX/*", &tree).is_empty());
+    }
+
+    #[test]
+    fn document_order() {
+        let mut data = b"FORM\0\0\0\x24ILBMLIST\0\0\0\x0eINFONAME\0\0\0\x02hi".to_vec();
+        data.extend_from_slice(b"NAME\0\0\0\x02hi");
+        let tree = iff::ChunkReader::new(Cursor::new(data)).unwrap().read_tree().unwrap();
+        assert_eq!(offsets("**/NAME", &tree), [24, 34]);
+        assert_eq!(offsets("FORM/*/**", &tree), [12, 24, 34]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |s: &str| Query::parse(s).map(|_| ()).map_err(|err| (err.column(), err.kind()));
+        assert_eq!(error(""), Err((0, ParseErrorKind::Empty)));
+        assert_eq!(error("moov//trak"), Err((5, ParseErrorKind::Empty)));
+        assert_eq!(error("moov/trak[1"), Err((9, ParseErrorKind::Unterminated)));
+        assert_eq!(error("moov/trak[x]"), Err((10, ParseErrorKind::InvalidNumber)));
+        assert_eq!(error("moovs"), Err((4, ParseErrorKind::TooLong)));
+        assert_eq!(error("LIST:"), Err((5, ParseErrorKind::Empty)));
+        assert_eq!(error("a*"), Err((1, ParseErrorKind::InvalidChar)));
+        assert_eq!(error("€"), Err((0, ParseErrorKind::InvalidChar)));
+    }
+
+    #[test]
+    fn display() {
+        for query in ["moov/trak[1]/mdia/hdlr", "RIFF:WAVE/LIST:INFO/INAM", "**/*/fmt", "*:INFO[0]"] {
+            assert_eq!(query.parse::<Query>().unwrap().to_string(), query);
+        }
+    }
+}
@@ -0,0 +1,358 @@
+//! Registry of well-known four character codes.
+//!
+//! The registry contains curated tables of commonly encountered codes grouped
+//! by [`Domain`]. The same code may appear in several domains with different
+//! meanings, for example `LIST` is both an IFF and a RIFF chunk.
+//!
+//! # Examples
+//! ```
+//! use fourcc::FourCC;
+//! use fourcc::registry::Domain;
+//!
+//! let info = FourCC(*b"avc1").describe(Domain::VideoCodec).unwrap();
+//! assert_eq!(info.name, "H.264/AVC");
+//! ```
+
+use crate::FourCC;
+
+/// Domain a four character code belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Domain {
+    /// Compressed video codec identifiers (AVI, QuickTime, MP4 sample entries).
+    VideoCodec,
+    /// Uncompressed and block compressed pixel formats (DirectShow, V4L2, DDS).
+    PixelFormat,
+    /// EA IFF-85 chunk and form identifiers.
+    IffChunk,
+    /// Microsoft RIFF chunk and list identifiers.
+    RiffChunk,
+    /// ISO base media file format box types.
+    IsoBmffBox,
+    /// OpenType table tags.
+    OpenTypeTable,
+}
+
+/// Description of a well-known four character code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Info {
+    /// The four character code.
+    pub code: FourCC,
+    /// Short human readable name.
+    pub name: &'static str,
+    /// One line description of the code.
+    pub description: &'static str,
+    /// Domain the code belongs to.
+    pub domain: Domain,
+    /// URL of the specification or registry defining the code.
+    pub reference: &'static str,
+}
+
+//------------------------------------------------------------------------------
+
+const MP4RA_CODECS: &str = "https://mp4ra.org/registered-types/codecs";
+const MP4RA_BOXES: &str = "https://mp4ra.org/registered-types/boxes";
+const FOURCC_ORG: &str = "https://www.fourcc.org/";
+const MS_YUV: &str = "https://learn.microsoft.com/en-us/windows/win32/medfound/recommended-8-bit-yuv-formats-for-video-rendering";
+const MS_YUV_10BIT: &str = "https://learn.microsoft.com/en-us/windows/win32/medfound/10-bit-and-16-bit-yuv-video-formats";
+const MS_DDS: &str = "https://learn.microsoft.com/en-us/windows/win32/direct3ddds/dx-graphics-dds-pguide";
+const DRM_FOURCC: &str = "https://github.com/torvalds/linux/blob/master/include/uapi/drm/drm_fourcc.h";
+const EA_IFF_85: &str = "https://wiki.amigaos.net/wiki/EA_IFF_85_Standard_for_Interchange_Format_Files";
+const ILBM: &str = "https://wiki.amigaos.net/wiki/ILBM_IFF_Interleaved_Bitmap";
+const SVX: &str = "https://wiki.amigaos.net/wiki/8SVX_IFF_8-Bit_Sampled_Voice";
+const AIFF: &str = "https://www.loc.gov/preservation/digital/formats/fdd/fdd000005.shtml";
+const MS_RIFF: &str = "https://learn.microsoft.com/en-us/windows/win32/xaudio2/resource-interchange-file-format--riff-";
+const MS_AVI: &str = "https://learn.microsoft.com/en-us/windows/win32/directshow/avi-riff-file-reference";
+const WEBP: &str = "https://developers.google.com/speed/webp/docs/riff_container";
+const OPENTYPE: &str = "https://learn.microsoft.com/en-us/typography/opentype/spec/otff";
+
+// Builds a static table of registry entries for a single domain.
+macro_rules! table {
+    ($domain:ident: $(($code:literal, $name:literal, $description:literal, $reference:expr)),* $(,)?) => {
+        &[$(Info {
+            code: FourCC::from_str_const($code),
+            name: $name,
+            description: $description,
+            domain: Domain::$domain,
+            reference: $reference,
+        }),*]
+    };
+}
+
+/// Well-known video codec identifiers.
+pub static VIDEO_CODECS: &[Info] = table![VideoCodec:
+    ("avc1", "H.264/AVC", "Advanced Video Coding, parameter sets in sample entry", MP4RA_CODECS),
+    ("avc3", "H.264/AVC", "Advanced Video Coding, parameter sets in band", MP4RA_CODECS),
+    ("hvc1", "H.265/HEVC", "High Efficiency Video Coding, parameter sets in sample entry", MP4RA_CODECS),
+    ("hev1", "H.265/HEVC", "High Efficiency Video Coding, parameter sets in band", MP4RA_CODECS),
+    ("vvc1", "H.266/VVC", "Versatile Video Coding, parameter sets in sample entry", MP4RA_CODECS),
+    ("av01", "AV1", "AOMedia Video 1", MP4RA_CODECS),
+    ("vp08", "VP8", "WebM Project VP8", MP4RA_CODECS),
+    ("vp09", "VP9", "WebM Project VP9", MP4RA_CODECS),
+    ("mp4v", "MPEG-4 Visual", "MPEG-4 Part 2 visual stream", MP4RA_CODECS),
+    ("dvh1", "Dolby Vision HEVC", "Dolby Vision over HEVC, parameter sets in sample entry", MP4RA_CODECS),
+    ("dvhe", "Dolby Vision HEVC", "Dolby Vision over HEVC, parameter sets in band", MP4RA_CODECS),
+    ("apch", "ProRes 422 HQ", "Apple ProRes 422 High Quality", MP4RA_CODECS),
+    ("apcn", "ProRes 422", "Apple ProRes 422", MP4RA_CODECS),
+    ("ap4h", "ProRes 4444", "Apple ProRes 4444", MP4RA_CODECS),
+    ("H264", "H.264/AVC", "H.264 elementary stream in AVI", FOURCC_ORG),
+    ("X264", "x264", "H.264 encoded by x264", FOURCC_ORG),
+    ("XVID", "Xvid", "Xvid MPEG-4 Part 2", FOURCC_ORG),
+    ("DIVX", "DivX", "DivX MPEG-4 Part 2", FOURCC_ORG),
+    ("DX50", "DivX 5", "DivX 5 MPEG-4 Part 2", FOURCC_ORG),
+    ("MJPG", "Motion JPEG", "Sequence of independently coded JPEG images", FOURCC_ORG),
+];
+
+/// Well-known pixel format identifiers.
+pub static PIXEL_FORMATS: &[Info] = table![PixelFormat:
+    ("YUY2", "YUY2", "Packed 4:2:2 YUV, Y0 U0 Y1 V0 byte order", MS_YUV),
+    ("YUYV", "YUYV", "Packed 4:2:2 YUV, alias of YUY2", MS_YUV),
+    ("UYVY", "UYVY", "Packed 4:2:2 YUV, U0 Y0 V0 Y1 byte order", MS_YUV),
+    ("YVYU", "YVYU", "Packed 4:2:2 YUV, Y0 V0 Y1 U0 byte order", MS_YUV),
+    ("AYUV", "AYUV", "Packed 4:4:4 YUV with alpha", MS_YUV),
+    ("NV12", "NV12", "Planar 4:2:0 YUV, interleaved UV plane", MS_YUV),
+    ("NV21", "NV21", "Planar 4:2:0 YUV, interleaved VU plane", DRM_FOURCC),
+    ("YV12", "YV12", "Planar 4:2:0 YUV, V plane before U plane", MS_YUV),
+    ("I420", "I420", "Planar 4:2:0 YUV, U plane before V plane", MS_YUV),
+    ("IYUV", "IYUV", "Planar 4:2:0 YUV, alias of I420", MS_YUV),
+    ("P010", "P010", "Planar 4:2:0 YUV, 10 bits per sample", MS_YUV_10BIT),
+    ("P016", "P016", "Planar 4:2:0 YUV, 16 bits per sample", MS_YUV_10BIT),
+    ("Y210", "Y210", "Packed 4:2:2 YUV, 10 bits per sample", MS_YUV_10BIT),
+    ("Y410", "Y410", "Packed 4:4:4 YUV with alpha, 10 bits per sample", MS_YUV_10BIT),
+    ("AR24", "ARGB8888", "Packed 32-bit ARGB, little-endian", DRM_FOURCC),
+    ("XR24", "XRGB8888", "Packed 32-bit RGB with unused byte, little-endian", DRM_FOURCC),
+    ("AB24", "ABGR8888", "Packed 32-bit ABGR, little-endian", DRM_FOURCC),
+    ("DXT1", "BC1", "Block compressed RGB with 1-bit alpha", MS_DDS),
+    ("DXT3", "BC2", "Block compressed RGBA with explicit alpha", MS_DDS),
+    ("DXT5", "BC3", "Block compressed RGBA with interpolated alpha", MS_DDS),
+    ("ATI2", "BC5", "Block compressed two channel normal map", MS_DDS),
+];
+
+/// Well-known EA IFF-85 chunk identifiers.
+pub static IFF_CHUNKS: &[Info] = table![IffChunk:
+    ("FORM", "FORM", "Group of chunks with a form type", EA_IFF_85),
+    ("LIST", "LIST", "Group of FORMs, CATs and LISTs sharing properties", EA_IFF_85),
+    ("CAT ", "CAT", "Concatenation of FORMs, CATs and LISTs", EA_IFF_85),
+    ("PROP", "PROP", "Shared properties for FORMs within a LIST", EA_IFF_85),
+    ("ANNO", "Annotation", "Free form annotation text", EA_IFF_85),
+    ("AUTH", "Author", "Name of the author", EA_IFF_85),
+    ("NAME", "Name", "Name of the art, music or other work", EA_IFF_85),
+    ("(c) ", "Copyright", "Copyright notice", EA_IFF_85),
+    ("ILBM", "ILBM", "Interleaved bitmap form type", ILBM),
+    ("BMHD", "Bitmap header", "ILBM raster dimensions and encoding", ILBM),
+    ("CMAP", "Colour map", "ILBM palette entries", ILBM),
+    ("BODY", "Body", "ILBM or 8SVX data", ILBM),
+    ("8SVX", "8SVX", "8-bit sampled voice form type", SVX),
+    ("VHDR", "Voice header", "8SVX sample rate and playback parameters", SVX),
+    ("AIFF", "AIFF", "Audio Interchange File Format form type", AIFF),
+    ("AIFC", "AIFF-C", "Compressed Audio Interchange File Format form type", AIFF),
+    ("COMM", "Common", "AIFF channel count, frames and sample format", AIFF),
+    ("SSND", "Sound data", "AIFF sample frames", AIFF),
+];
+
+/// Well-known RIFF chunk identifiers.
+pub static RIFF_CHUNKS: &[Info] = table![RiffChunk:
+    ("RIFF", "RIFF", "Little-endian RIFF file header", MS_RIFF),
+    ("RIFX", "RIFX", "Big-endian RIFF file header", MS_RIFF),
+    ("LIST", "LIST", "Group of chunks with a list type", MS_RIFF),
+    ("WAVE", "WAVE", "Waveform audio form type", MS_RIFF),
+    ("fmt ", "Format", "WAVE sample format", MS_RIFF),
+    ("data", "Data", "WAVE sample data", MS_RIFF),
+    ("fact", "Fact", "WAVE sample length for compressed formats", MS_RIFF),
+    ("cue ", "Cue points", "WAVE cue point list", MS_RIFF),
+    ("INFO", "INFO", "List of metadata strings", MS_RIFF),
+    ("INAM", "Name", "Title of the subject", MS_RIFF),
+    ("IART", "Artist", "Artist of the original subject", MS_RIFF),
+    ("ICMT", "Comment", "General comments", MS_RIFF),
+    ("AVI ", "AVI", "Audio Video Interleave form type", MS_AVI),
+    ("hdrl", "Header list", "AVI main and stream headers", MS_AVI),
+    ("avih", "Main header", "AVI global stream information", MS_AVI),
+    ("strl", "Stream list", "AVI per-stream headers", MS_AVI),
+    ("strh", "Stream header", "AVI stream type and rate", MS_AVI),
+    ("strf", "Stream format", "AVI stream specific format", MS_AVI),
+    ("movi", "Movie list", "AVI stream data", MS_AVI),
+    ("idx1", "Index", "AVI 1.0 chunk index", MS_AVI),
+    ("WEBP", "WebP", "WebP image form type", WEBP),
+    ("VP8 ", "VP8", "Lossy WebP bitstream", WEBP),
+    ("VP8L", "VP8L", "Lossless WebP bitstream", WEBP),
+    ("VP8X", "VP8X", "Extended WebP header", WEBP),
+];
+
+/// Well-known ISO base media file format box types.
+pub static ISOBMFF_BOXES: &[Info] = table![IsoBmffBox:
+    ("ftyp", "File type", "Major brand and compatible brands", MP4RA_BOXES),
+    ("moov", "Movie", "Container for presentation metadata", MP4RA_BOXES),
+    ("mvhd", "Movie header", "Presentation timescale and duration", MP4RA_BOXES),
+    ("trak", "Track", "Container for a single track", MP4RA_BOXES),
+    ("tkhd", "Track header", "Track dimensions and flags", MP4RA_BOXES),
+    ("edts", "Edit", "Container for the edit list", MP4RA_BOXES),
+    ("elst", "Edit list", "Mapping of presentation to media time", MP4RA_BOXES),
+    ("mdia", "Media", "Container for track media information", MP4RA_BOXES),
+    ("mdhd", "Media header", "Media timescale, duration and language", MP4RA_BOXES),
+    ("hdlr", "Handler", "Media handler type", MP4RA_BOXES),
+    ("minf", "Media information", "Container for media information", MP4RA_BOXES),
+    ("dinf", "Data information", "Container for data references", MP4RA_BOXES),
+    ("dref", "Data reference", "Table of data references", MP4RA_BOXES),
+    ("stbl", "Sample table", "Container for sample tables", MP4RA_BOXES),
+    ("stsd", "Sample descriptions", "Codec sample entries", MP4RA_BOXES),
+    ("stts", "Decoding time to sample", "Sample durations", MP4RA_BOXES),
+    ("stsc", "Sample to chunk", "Sample to chunk mapping", MP4RA_BOXES),
+    ("stsz", "Sample sizes", "Sample size table", MP4RA_BOXES),
+    ("stco", "Chunk offsets", "32-bit chunk offset table", MP4RA_BOXES),
+    ("co64", "Chunk offsets", "64-bit chunk offset table", MP4RA_BOXES),
+    ("mvex", "Movie extends", "Fragmented movie defaults", MP4RA_BOXES),
+    ("moof", "Movie fragment", "Container for fragment metadata", MP4RA_BOXES),
+    ("mfhd", "Movie fragment header", "Fragment sequence number", MP4RA_BOXES),
+    ("traf", "Track fragment", "Container for track fragment metadata", MP4RA_BOXES),
+    ("tfhd", "Track fragment header", "Track fragment defaults", MP4RA_BOXES),
+    ("trun", "Track fragment run", "Samples within a track fragment", MP4RA_BOXES),
+    ("sidx", "Segment index", "Index of media segment subsegments", MP4RA_BOXES),
+    ("mdat", "Media data", "Sample data", MP4RA_BOXES),
+    ("udta", "User data", "Container for user metadata", MP4RA_BOXES),
+    ("meta", "Metadata", "Container for metadata items", MP4RA_BOXES),
+    ("pssh", "Protection system", "Content protection system specific header", MP4RA_BOXES),
+    ("uuid", "UUID", "User extension box with 16 byte extended type", MP4RA_BOXES),
+    ("free", "Free space", "Unused space", MP4RA_BOXES),
+    ("skip", "Free space", "Unused space", MP4RA_BOXES),
+];
+
+/// Well-known OpenType table tags.
+pub static OPENTYPE_TABLES: &[Info] = table![OpenTypeTable:
+    ("cmap", "Character map", "Mapping of characters to glyph indices", OPENTYPE),
+    ("head", "Font header", "Global font information", OPENTYPE),
+    ("hhea", "Horizontal header", "Horizontal layout metrics", OPENTYPE),
+    ("hmtx", "Horizontal metrics", "Per glyph horizontal metrics", OPENTYPE),
+    ("maxp", "Maximum profile", "Memory requirements of the font", OPENTYPE),
+    ("name", "Naming table", "Localised font names and strings", OPENTYPE),
+    ("OS/2", "OS/2 and Windows metrics", "Windows specific metrics", OPENTYPE),
+    ("post", "PostScript", "PostScript printer information", OPENTYPE),
+    ("glyf", "Glyph data", "TrueType outlines", OPENTYPE),
+    ("loca", "Index to location", "Offsets of glyphs in the glyf table", OPENTYPE),
+    ("cvt ", "Control value", "TrueType hinting control values", OPENTYPE),
+    ("fpgm", "Font program", "TrueType hinting font program", OPENTYPE),
+    ("prep", "Control value program", "TrueType hinting pre-program", OPENTYPE),
+    ("CFF ", "Compact font format", "CFF version 1 outlines", OPENTYPE),
+    ("CFF2", "Compact font format 2", "CFF version 2 outlines", OPENTYPE),
+    ("GDEF", "Glyph definition", "Glyph classes and attachment points", OPENTYPE),
+    ("GPOS", "Glyph positioning", "Positioning adjustments", OPENTYPE),
+    ("GSUB", "Glyph substitution", "Glyph substitutions", OPENTYPE),
+    ("kern", "Kerning", "Legacy pair kerning", OPENTYPE),
+    ("DSIG", "Digital signature", "Font digital signature", OPENTYPE),
+];
+
+//------------------------------------------------------------------------------
+
+/// Returns the table of known codes for a domain.
+pub fn table(domain: Domain) -> &'static [Info] {
+    match domain {
+        Domain::VideoCodec => VIDEO_CODECS,
+        Domain::PixelFormat => PIXEL_FORMATS,
+        Domain::IffChunk => IFF_CHUNKS,
+        Domain::RiffChunk => RIFF_CHUNKS,
+        Domain::IsoBmffBox => ISOBMFF_BOXES,
+        Domain::OpenTypeTable => OPENTYPE_TABLES,
+    }
+}
+
+/// Every domain known to the registry.
+pub const DOMAINS: [Domain; 6] = [
+    Domain::VideoCodec,
+    Domain::PixelFormat,
+    Domain::IffChunk,
+    Domain::RiffChunk,
+    Domain::IsoBmffBox,
+    Domain::OpenTypeTable,
+];
+
+/// Looks up a code in the table of a single domain.
+pub fn lookup(code: FourCC, domain: Domain) -> Option<&'static Info>
+    { table(domain).iter().find(|info| info.code == code) }
+
+/// Returns every registry entry for a code, across all domains.
+pub fn find(code: FourCC) -> impl Iterator<Item = &'static Info> {
+    DOMAINS.into_iter()
+        .flat_map(table)
+        .filter(move |info| info.code == code)
+}
+
+/// Looks up a code in the table of a single domain, ignoring the case of
+/// ASCII letters.
+///
+/// An exact match is preferred over one differing in case.
+///
+/// # Examples
+/// ```
+/// use fourcc::FourCC;
+/// use fourcc::registry::{self, Domain};
+///
+/// let info = registry::lookup_ignore_case(FourCC(*b"xvid"), Domain::VideoCodec);
+/// assert_eq!(info.unwrap().code, "XVID");
+/// ```
+pub fn lookup_ignore_case(code: FourCC, domain: Domain) -> Option<&'static Info> {
+    lookup(code, domain)
+        .or_else(|| table(domain).iter().find(|info| info.code.eq_ignore_ascii_case(&code)))
+}
+
+/// Returns every registry entry for a code across all domains, ignoring the
+/// case of ASCII letters.
+pub fn find_ignore_case(code: FourCC) -> impl Iterator<Item = &'static Info> {
+    DOMAINS.into_iter()
+        .flat_map(table)
+        .filter(move |info| info.code.eq_ignore_ascii_case(&code))
+}
+
+impl FourCC {
+    /// Describes the `FourCC` using the registry table of a domain.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::FourCC;
+    /// use fourcc::registry::Domain;
+    ///
+    /// let info = FourCC(*b"fmt ").describe(Domain::RiffChunk).unwrap();
+    /// assert_eq!(info.name, "Format");
+    /// assert!(FourCC(*b"fmt ").describe(Domain::IffChunk).is_none());
+    /// ```
+    pub fn describe(&self, domain: Domain) -> Option<&'static Info>
+        { lookup(*self, domain) }
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe() {
+        let info = FourCC(*b"YUY2").describe(Domain::PixelFormat).unwrap();
+        assert_eq!(info.code, "YUY2");
+        assert_eq!(info.domain, Domain::PixelFormat);
+        assert!(FourCC(*b"YUY2").describe(Domain::VideoCodec).is_none());
+    }
+
+    #[test]
+    fn find_across_domains() {
+        let domains: Vec<Domain> = find(FourCC(*b"LIST")).map(|info| info.domain).collect();
+        assert_eq!(domains, [Domain::IffChunk, Domain::RiffChunk]);
+    }
+
+    #[test]
+    fn ignore_case() {
+        assert!(lookup(FourCC(*b"divx"), Domain::VideoCodec).is_none());
+        assert_eq!(lookup_ignore_case(FourCC(*b"divx"), Domain::VideoCodec).unwrap().code, "DIVX");
+        let domains: Vec<Domain> = find_ignore_case(FourCC(*b"list")).map(|info| info.domain).collect();
+        assert_eq!(domains, [Domain::IffChunk, Domain::RiffChunk]);
+    }
+
+    #[test]
+    fn tables_are_consistent() {
+        for domain in DOMAINS {
+            let entries = table(domain);
+            for (i, info) in entries.iter().enumerate() {
+                assert_eq!(info.domain, domain);
+                assert!(info.reference.starts_with("https://"));
+                assert!(!entries[..i].iter().any(|other| other.code == info.code), "duplicate {:?}", info.code);
+            }
+        }
+    }
+}
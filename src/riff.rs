@@ -0,0 +1,113 @@
+//! RIFF and RIFX chunk reader.
+//!
+//! RIFF shares the chunk layout of IFF-85 with little-endian chunk sizes, its
+//! big-endian variant RIFX uses the IFF byte order. The `RIFF`, `RIFX` and
+//! `LIST` chunks are groups whose payload begins with a list type. Chunks are
+//! read with the [`ChunkReader`] of the [`iff`](crate::iff) module and written
+//! with its [`ChunkWriter`](crate::iff::ChunkWriter) in little-endian byte order.
+//!
+//! # Examples
+//! ```
+//! use std::io::Cursor;
+//! use fourcc::riff;
+//!
+//! let data = b"RIFF\x10\0\0\0WAVEdata\x03\0\0\0abc\0";
+//! let tree = riff::read_tree(Cursor::new(&data[..])).unwrap();
+//! assert_eq!(tree[0].chunk.form_type.unwrap(), "WAVE");
+//! assert_eq!(tree[0].children[0].chunk.id, "data");
+//! assert_eq!(tree[0].children[0].chunk.offset, 12);
+//! ```
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::iff::{ChunkReader, Node};
+use crate::{ByteOrder, FourCC};
+
+/// Little-endian RIFF file header.
+pub const RIFF: FourCC = FourCC(*b"RIFF");
+/// Big-endian RIFF file header.
+pub const RIFX: FourCC = FourCC(*b"RIFX");
+/// Group of chunks with a list type.
+pub const LIST: FourCC = FourCC(*b"LIST");
+
+/// Checks whether a chunk identifier denotes a RIFF group chunk.
+pub fn is_group(id: FourCC) -> bool
+    { id == RIFF || id == RIFX || id == LIST }
+
+/// Creates a chunk reader for a RIFF or RIFX stream.
+///
+/// The byte order of the chunk sizes is detected from the identifier of the
+/// first chunk at the current position, the stream is left at that position.
+pub fn open<R: Read + Seek>(mut reader: R) -> io::Result<ChunkReader<R>> {
+    let start = reader.stream_position()?;
+    let mut id = [0; 4];
+    reader.read_exact(&mut id)?;
+    reader.seek(SeekFrom::Start(start))?;
+    let order = match FourCC(id) {
+        RIFF => ByteOrder::Little,
+        RIFX => ByteOrder::Big,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "not a RIFF or RIFX stream")),
+    };
+    ChunkReader::with_format(reader, order, is_group)
+}
+
+/// Reads a RIFF or RIFX stream into a tree of chunks.
+pub fn read_tree<R: Read + Seek>(reader: R) -> io::Result<Vec<Node>>
+    { open(reader)?.read_tree() }
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn wave(order: ByteOrder) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(if order == ByteOrder::Little { b"RIFF" } else { b"RIFX" });
+        data.extend_from_slice(&order.write_u32(0x2f));
+        data.extend_from_slice(b"WAVEfmt ");
+        data.extend_from_slice(&order.write_u32(3));
+        data.extend_from_slice(b"abc\0LIST");
+        data.extend_from_slice(&order.write_u32(0x0e));
+        data.extend_from_slice(b"INFOINAM");
+        data.extend_from_slice(&order.write_u32(2));
+        data.extend_from_slice(b"hidata");
+        data.extend_from_slice(&order.write_u32(1));
+        data.extend_from_slice(b"x");
+        data
+    }
+
+    #[test]
+    fn read_riff() {
+        for order in [ByteOrder::Little, ByteOrder::Big] {
+            let tree = read_tree(Cursor::new(wave(order))).unwrap();
+            assert_eq!(tree.len(), 1);
+            let chunks: Vec<(FourCC, u64, u32)> = tree[0].children.iter()
+                .map(|node| (node.chunk.id, node.chunk.offset, node.chunk.size))
+                .collect();
+            assert_eq!(chunks, [
+                (FourCC(*b"fmt "), 12, 3),
+                (LIST, 24, 14),
+                (FourCC(*b"data"), 46, 1),
+            ]);
+            assert_eq!(tree[0].children[1].chunk.form_type, Some(FourCC(*b"INFO")));
+            assert_eq!(tree[0].children[1].children[0].chunk.id, "INAM");
+        }
+    }
+
+    #[test]
+    fn read_payload() {
+        let mut cursor = Cursor::new(wave(ByteOrder::Little));
+        let tree = read_tree(&mut cursor).unwrap();
+        let mut data = Vec::new();
+        tree[0].children[0].chunk.payload(&mut cursor).unwrap().read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"abc");
+    }
+
+    #[test]
+    fn not_riff() {
+        let err = open(Cursor::new(b"FORM\0\0\0\x04ILBM")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
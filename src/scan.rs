@@ -0,0 +1,276 @@
+//! Searching byte buffers for occurrences of four character codes.
+//!
+//! [`find_all`] locates any of a set of codes at once, such as when carving
+//! `RIFF`, `FORM` or `moov` headers out of damaged media. Candidate offsets
+//! are found 32 bytes at a time by comparing the first two bytes of every
+//! code using AVX2 or SSE2 where available, then confirmed with a full
+//! comparison.
+//!
+//! # Examples
+//! ```
+//! use fourcc::{scan, FourCC};
+//!
+//! let data = b"..RIFF....moovRIFF";
+//! let codes = [FourCC(*b"RIFF"), FourCC(*b"moov")];
+//! let found: Vec<(usize, FourCC)> = scan::find_all(data, &codes).collect();
+//! assert_eq!(found, [(2, codes[0]), (10, codes[1]), (14, codes[0])]);
+//!
+//! let aligned: Vec<usize> = scan::find_all(data, &codes).aligned(4).map(|(i, _)| i).collect();
+//! assert_eq!(aligned, []);
+//! ```
+
+use crate::FourCC;
+
+/// Number of offsets examined per block.
+const BLOCK: usize = 32;
+
+/// Implementation used to find candidate offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// Portable byte by byte comparison.
+    Scalar,
+    /// 16 byte x86 SSE2 comparison.
+    Sse2,
+    /// 32 byte x86 AVX2 comparison.
+    Avx2,
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), feature = "std"))]
+macro_rules! has_feature {
+    ($feature:tt) => { std::is_x86_feature_detected!($feature) };
+}
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), not(feature = "std")))]
+macro_rules! has_feature {
+    ($feature:tt) => { cfg!(target_feature = $feature) };
+}
+
+impl Method {
+    /// Fastest method supported by the running CPU.
+    ///
+    /// Without the `std` feature, only methods enabled at compile time are
+    /// detected.
+    pub fn detect() -> Self {
+        [Self::Avx2, Self::Sse2].into_iter()
+            .find(|method| method.is_supported())
+            .unwrap_or(Self::Scalar)
+    }
+
+    /// Checks whether the running CPU supports the method.
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::Scalar => true,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Self::Sse2 => has_feature!("sse2"),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Self::Avx2 => has_feature!("avx2"),
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+            _ => false,
+        }
+    }
+
+    // Returns a bit for every offset of the block whose first two bytes match
+    // those of a code, the block must be at least `BLOCK + 1` bytes long.
+    fn candidates(self, block: &[u8], codes: &[FourCC]) -> u32 {
+        debug_assert!(block.len() > BLOCK);
+        match self {
+            // SAFETY: methods are only used once supported, see `Scan::method`
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Self::Sse2 => unsafe { x86::sse2(block, codes) },
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Self::Avx2 => unsafe { x86::avx2(block, codes) },
+            _ => scalar(block, codes, BLOCK),
+        }
+    }
+}
+
+fn scalar(block: &[u8], codes: &[FourCC], len: usize) -> u32 {
+    let mut mask = 0;
+    for i in 0..len.min(block.len().saturating_sub(1)) {
+        if codes.iter().any(|code| code.0[0] == block[i] && code.0[1] == block[i + 1]) { mask |= 1 << i }
+    }
+    mask
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    use super::BLOCK;
+    use crate::FourCC;
+
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn sse2(block: &[u8], codes: &[FourCC]) -> u32 {
+        let ptr = block.as_ptr();
+        let mut mask = 0;
+        for half in [0, BLOCK / 2] {
+            let first = _mm_loadu_si128(ptr.add(half) as *const __m128i);
+            let second = _mm_loadu_si128(ptr.add(half + 1) as *const __m128i);
+            let mut hits = _mm_setzero_si128();
+            for code in codes {
+                let a = _mm_cmpeq_epi8(first, _mm_set1_epi8(code.0[0] as i8));
+                let b = _mm_cmpeq_epi8(second, _mm_set1_epi8(code.0[1] as i8));
+                hits = _mm_or_si128(hits, _mm_and_si128(a, b));
+            }
+            mask |= (_mm_movemask_epi8(hits) as u32 & 0xffff) << half;
+        }
+        mask
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn avx2(block: &[u8], codes: &[FourCC]) -> u32 {
+        let ptr = block.as_ptr();
+        let first = _mm256_loadu_si256(ptr as *const __m256i);
+        let second = _mm256_loadu_si256(ptr.add(1) as *const __m256i);
+        let mut hits = _mm256_setzero_si256();
+        for code in codes {
+            let a = _mm256_cmpeq_epi8(first, _mm256_set1_epi8(code.0[0] as i8));
+            let b = _mm256_cmpeq_epi8(second, _mm256_set1_epi8(code.0[1] as i8));
+            hits = _mm256_or_si256(hits, _mm256_and_si256(a, b));
+        }
+        _mm256_movemask_epi8(hits) as u32
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// Iterator over the offsets of codes within a byte buffer, created by
+/// [`find_all`].
+///
+/// Occurrences are yielded in order of their offset, including overlapping
+/// ones.
+#[derive(Debug, Clone)]
+pub struct Scan<'a> {
+    haystack: &'a [u8],
+    codes: &'a [FourCC],
+    method: Method,
+    align: usize,
+    block: usize,
+    next: usize,
+    mask: u32,
+}
+
+/// Returns an iterator over every occurrence of any of the codes within a
+/// byte buffer, yielding the offset and matching code.
+pub fn find_all<'a>(haystack: &'a [u8], codes: &'a [FourCC]) -> Scan<'a> {
+    let next = if codes.is_empty() { haystack.len() } else { 0 };
+    Scan { haystack, codes, method: Method::detect(), align: 1, block: 0, next, mask: 0 }
+}
+
+impl Scan<'_> {
+    /// Only yields occurrences at multiples of `align`, such as `2` for the
+    /// word aligned chunks of IFF and RIFF.
+    ///
+    /// # Panics
+    /// Panics if `align` is zero.
+    pub fn aligned(mut self, align: usize) -> Self {
+        assert!(align > 0, "alignment must be non-zero");
+        self.align = align;
+        self
+    }
+
+    /// Uses the given method to find candidates, falling back to the scalar
+    /// method if it is not supported by the running CPU.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = if method.is_supported() { method } else { Method::Scalar };
+        self
+    }
+
+    fn confirm(&self, offset: usize) -> Option<(usize, FourCC)> {
+        if !offset.is_multiple_of(self.align) { return None }
+        let window = self.haystack.get(offset..offset + 4)?;
+        self.codes.iter().find(|code| code.0 == window).map(|&code| (offset, code))
+    }
+}
+
+impl Iterator for Scan<'_> {
+    type Item = (usize, FourCC);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while self.mask != 0 {
+                let offset = self.block + self.mask.trailing_zeros() as usize;
+                self.mask &= self.mask - 1;
+                if let Some(found) = self.confirm(offset) { return Some(found) }
+            }
+            let len = self.haystack.len();
+            if self.next + 4 > len { return None }
+            self.block = self.next;
+            let block = &self.haystack[self.block..];
+            if block.len() > BLOCK {
+                self.mask = self.method.candidates(block, self.codes);
+                self.next += BLOCK;
+            } else {
+                self.mask = scalar(block, self.codes, block.len());
+                self.next = len;
+            }
+        }
+    }
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODES: [FourCC; 3] = [FourCC(*b"RIFF"), FourCC(*b"moov"), FourCC(*b"FORM")];
+
+    fn naive(haystack: &[u8], codes: &[FourCC], align: usize) -> Vec<(usize, FourCC)> {
+        (0..haystack.len().saturating_sub(3))
+            .filter(|i| i.is_multiple_of(align))
+            .filter_map(|i| codes.iter().find(|code| code.0 == haystack[i..i + 4]).map(|&code| (i, code)))
+            .collect()
+    }
+
+    fn haystack() -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        let mut data: Vec<u8> = (0..4096).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            b"RIFmovFORM"[state as usize % 10]
+        }).collect();
+        for offset in [0, 31, 32, 63, 100, 4092] {
+            data[offset..offset + 4].copy_from_slice(&CODES[offset % 3].0);
+        }
+        data
+    }
+
+    #[test]
+    fn methods_agree() {
+        let data = haystack();
+        for method in [Method::Scalar, Method::Sse2, Method::Avx2] {
+            for align in [1, 2, 3, 4] {
+                for len in [0, 3, 4, 33, 64, 65, 4000, data.len()] {
+                    let found: Vec<_> = find_all(&data[..len], &CODES).method(method).aligned(align).collect();
+                    assert_eq!(found, naive(&data[..len], &CODES, align), "{method:?} align {align} len {len}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn overlapping() {
+        let code = [FourCC(*b"aaaa")];
+        let found: Vec<usize> = find_all(b"aaaaaa", &code).map(|(i, _)| i).collect();
+        assert_eq!(found, [0, 1, 2]);
+        assert_eq!(find_all(b"aaaaaa", &[]).count(), 0);
+    }
+
+    #[test]
+    fn unsupported_method() {
+        let scan = find_all(b"", &CODES).method(Method::Avx2);
+        assert!(scan.method == Method::Avx2 || scan.method == Method::Scalar);
+        assert!(Method::detect().is_supported());
+    }
+
+    #[test]
+    #[should_panic(expected = "alignment must be non-zero")]
+    fn zero_alignment() {
+        let _ = find_all(b"RIFF", &CODES).aligned(0);
+    }
+}
@@ -0,0 +1,88 @@
+//! Serde support for `FourCC`, enabled by the `serde` feature.
+//!
+//! Human readable formats represent a code as its four character string and
+//! binary formats as its four bytes. Deserialization validates the input
+//! with `TryFrom<&str>` and `TryFrom<&[u8]>`, so only codes of four graphic
+//! ASCII characters round-trip.
+
+use core::fmt;
+
+use ::serde::de::{self, Deserialize, Deserializer, Visitor};
+use ::serde::ser::{self, Serialize, Serializer};
+
+use crate::FourCC;
+
+impl Serialize for FourCC {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() { return serializer.serialize_bytes(&self.0) }
+        match core::str::from_utf8(&self.0) {
+            Ok(s) => serializer.serialize_str(s),
+            Err(_) => Err(ser::Error::custom(format_args!("four character code {self:?} is not valid UTF-8"))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FourCC {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(FourCCVisitor)
+        } else {
+            deserializer.deserialize_bytes(FourCCVisitor)
+        }
+    }
+}
+
+struct FourCCVisitor;
+
+impl Visitor<'_> for FourCCVisitor {
+    type Value = FourCC;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result
+        { f.write_str("four graphic ASCII characters") }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<FourCC, E> {
+        FourCC::try_from(v)
+            .map_err(|err| E::custom(format_args!("invalid four character code {v:?}: {err}")))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<FourCC, E> {
+        FourCC::try_from(v)
+            .map_err(|err| E::custom(format_args!("invalid four character code {v:?}: {err}")))
+    }
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let rgba = FourCC(*b"RGBA");
+        assert_eq!(serde_json::to_string(&rgba).unwrap(), r#""RGBA""#);
+        assert_eq!(serde_json::from_str::<FourCC>(r#""RGBA""#).unwrap(), rgba);
+        assert!(serde_json::to_string(&FourCC([0xff, 0, 0, 0])).is_err());
+
+        let error = |s: &str| serde_json::from_str::<FourCC>(s).unwrap_err().to_string();
+        assert!(error(r#""RGB""#).contains("too short (3 bytes)"));
+        assert!(error(r#""RGBA8""#).contains("too long (5 bytes)"));
+        assert!(error(r#""fmt ""#).contains("non-graphic byte at index 3"));
+        assert!(error(r#""RGé""#).contains("non-ASCII byte at index 2"));
+        assert!(error("1380401729").contains("expected four graphic ASCII characters"));
+    }
+
+    #[test]
+    fn bincode() {
+        let rgba = FourCC(*b"RGBA");
+        let bytes = bincode::serialize(&rgba).unwrap();
+        assert_eq!(bytes[bytes.len() - 4..], *b"RGBA");
+        assert_eq!(bincode::deserialize::<FourCC>(&bytes).unwrap(), rgba);
+
+        let error = |data: &[u8]| bincode::deserialize::<FourCC>(&bincode::serialize(data).unwrap()).unwrap_err().to_string();
+        assert!(error(b"RGB").contains("too short (3 bytes)"));
+        assert!(error(b"RGBA8").contains("too long (5 bytes)"));
+        assert!(error(b"RGB\0").contains("non-graphic byte at index 3"));
+        assert!(error(b"RG\xffA").contains("non-ASCII byte at index 2"));
+    }
+}
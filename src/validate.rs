@@ -0,0 +1,160 @@
+//! Validation of four character codes against format specific rules.
+
+use core::fmt;
+
+use crate::FourCC;
+
+/// Set of rules a four character code is validated against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationProfile {
+    /// EA IFF-85: printable ASCII, spaces only as trailing padding.
+    Iff85,
+    /// Microsoft RIFF: ASCII alphanumerics, right padded with spaces.
+    Riff,
+    /// ISO base media file format: any byte, such as QuickTime's `©nam`.
+    IsoBmff,
+    /// OpenType tag: printable ASCII with at least one non-space character,
+    /// right padded with spaces.
+    OpenType,
+    /// ASCII alphanumerics only.
+    StrictAlphanumeric,
+    /// PNG chunk type: ASCII letters only, see [`PngChunkType`](crate::png::PngChunkType).
+    Png,
+}
+
+/// Reason a byte failed validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationReason {
+    /// Byte is not printable ASCII.
+    NotPrintable,
+    /// Byte is not an ASCII letter or digit.
+    NotAlphanumeric,
+    /// Byte is not an ASCII letter.
+    NotAlphabetic,
+    /// Space is followed by a non-space character.
+    SpaceBeforeCharacter,
+    /// Code consists only of spaces.
+    AllSpaces,
+}
+
+/// Error returned when a `FourCC` fails validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ValidationError {
+    /// Index of the offending byte.
+    pub index: usize,
+    /// Value of the offending byte.
+    pub byte: u8,
+    /// Reason the byte is invalid.
+    pub reason: ValidationReason,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self.reason {
+            ValidationReason::NotPrintable => "is not printable ASCII",
+            ValidationReason::NotAlphanumeric => "is not an ASCII letter or digit",
+            ValidationReason::NotAlphabetic => "is not an ASCII letter",
+            ValidationReason::SpaceBeforeCharacter => "is a space before a non-space character",
+            ValidationReason::AllSpaces => "begins a code of only spaces",
+        };
+        write!(f, "byte {:#04x} at index {} {}", self.byte, self.index, reason)
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+//------------------------------------------------------------------------------
+
+impl FourCC {
+    /// Validates the `FourCC` against the rules of a profile.
+    ///
+    /// # Examples
+    /// ```
+    /// use fourcc::{FourCC, ValidationProfile, ValidationReason};
+    ///
+    /// assert!(FourCC(*b"fmt ").validate(ValidationProfile::Iff85).is_ok());
+    /// assert!(FourCC([0xa9, b'n', b'a', b'm']).validate(ValidationProfile::IsoBmff).is_ok());
+    ///
+    /// let err = FourCC(*b" fmt").validate(ValidationProfile::Iff85).unwrap_err();
+    /// assert_eq!(err.index, 0);
+    /// assert_eq!(err.reason, ValidationReason::SpaceBeforeCharacter);
+    /// ```
+    pub fn validate(&self, profile: ValidationProfile) -> Result<(), ValidationError> {
+        let error = |index: usize, reason| Err(ValidationError { index, byte: self.0[index], reason });
+        let (allowed, reason, padded): (fn(&u8) -> bool, _, _) = match profile {
+            ValidationProfile::IsoBmff => return Ok(()),
+            ValidationProfile::StrictAlphanumeric => (u8::is_ascii_alphanumeric, ValidationReason::NotAlphanumeric, false),
+            ValidationProfile::Png => (u8::is_ascii_alphabetic, ValidationReason::NotAlphabetic, false),
+            ValidationProfile::Riff => (u8::is_ascii_alphanumeric, ValidationReason::NotAlphanumeric, true),
+            ValidationProfile::Iff85 | ValidationProfile::OpenType => (u8::is_ascii_graphic, ValidationReason::NotPrintable, true),
+        };
+        for (i, b) in self.0.iter().enumerate() {
+            if padded && *b == b' ' { continue }
+            if !allowed(b) { return error(i, reason) }
+        }
+        if padded {
+            // spaces may only be followed by spaces
+            if let Some(space) = self.0.iter().position(|&b| b == b' ') {
+                if self.0[space..].iter().any(|&b| b != b' ') { return error(space, ValidationReason::SpaceBeforeCharacter) }
+                if space == 0 && profile != ValidationProfile::Iff85 { return error(0, ValidationReason::AllSpaces) }
+            }
+        }
+        Ok(())
+    }
+}
+
+//==============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reason(code: &[u8; 4], profile: ValidationProfile) -> Option<(usize, ValidationReason)>
+        { FourCC(*code).validate(profile).err().map(|err| (err.index, err.reason)) }
+
+    #[test]
+    fn iff85() {
+        use ValidationProfile::Iff85;
+        assert_eq!(reason(b"FORM", Iff85), None);
+        assert_eq!(reason(b"fmt ", Iff85), None);
+        assert_eq!(reason(b"(c) ", Iff85), None);
+        assert_eq!(reason(b"    ", Iff85), None);
+        assert_eq!(reason(b" fmt", Iff85), Some((0, ValidationReason::SpaceBeforeCharacter)));
+        assert_eq!(reason(b"a b ", Iff85), Some((1, ValidationReason::SpaceBeforeCharacter)));
+        assert_eq!(reason(b"fmt\0", Iff85), Some((3, ValidationReason::NotPrintable)));
+    }
+
+    #[test]
+    fn riff() {
+        use ValidationProfile::Riff;
+        assert_eq!(reason(b"fmt ", Riff), None);
+        assert_eq!(reason(b"ds64", Riff), None);
+        assert_eq!(reason(b"(c) ", Riff), Some((0, ValidationReason::NotAlphanumeric)));
+        assert_eq!(reason(b"    ", Riff), Some((0, ValidationReason::AllSpaces)));
+    }
+
+    #[test]
+    fn opentype() {
+        use ValidationProfile::OpenType;
+        assert_eq!(reason(b"OS/2", OpenType), None);
+        assert_eq!(reason(b"cvt ", OpenType), None);
+        assert_eq!(reason(b"    ", OpenType), Some((0, ValidationReason::AllSpaces)));
+        assert_eq!(reason(b"c vt", OpenType), Some((1, ValidationReason::SpaceBeforeCharacter)));
+    }
+
+    #[test]
+    fn other_profiles() {
+        assert_eq!(reason(&[0xa9, b'n', b'a', b'm'], ValidationProfile::IsoBmff), None);
+        assert_eq!(reason(&[0; 4], ValidationProfile::IsoBmff), None);
+        assert_eq!(reason(b"avc1", ValidationProfile::StrictAlphanumeric), None);
+        assert_eq!(reason(b"fmt ", ValidationProfile::StrictAlphanumeric), Some((3, ValidationReason::NotAlphanumeric)));
+        assert_eq!(reason(b"tEXt", ValidationProfile::Png), None);
+        assert_eq!(reason(b"IDA1", ValidationProfile::Png), Some((3, ValidationReason::NotAlphabetic)));
+    }
+
+    #[test]
+    fn display() {
+        let err = FourCC(*b"fmt\0").validate(ValidationProfile::Iff85).unwrap_err();
+        assert_eq!(err.to_string(), "byte 0x00 at index 3 is not printable ASCII");
+    }
+}